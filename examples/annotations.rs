@@ -0,0 +1,79 @@
+use fltk::{prelude::*, enums::*, app::*, draw::*};
+use fltk_plot::{widgets::annotation::*, windows::figure_window::*};
+
+fn linspace(arr: &mut Vec<f64>, l: f64, r: f64, len: usize) {
+    let dx = (r - l) / (len - 1) as f64;
+
+    arr.reserve(len);
+
+    for i in 0..len {
+        arr.push(l + (i as f64 * dx));
+    }
+}
+
+fn arrayfun(input: &[f64], output: &mut Vec<f64>, fun: &dyn Fn(f64) -> f64) {
+    output.reserve(input.len());
+
+    for i in input.iter() {
+        output.push(fun(*i));
+    }
+}
+
+fn main() {
+    let app = App::default();
+
+    const N: usize = 200;
+    let mut t: Vec<f64> = Vec::with_capacity(N);
+    let mut x: Vec<f64> = Vec::with_capacity(N);
+
+    linspace(&mut t, 0.0, 4.0 * std::f64::consts::PI, N);
+    arrayfun(&t, &mut x, &|t: f64| f64::exp(-0.2 * t) * f64::sin(2.0 * t));
+
+    let mut fig = FigureWindow::new(640, 480, "Annotations", 1, 1);
+    fig.plot(&t, &x, LineStyle::Solid, 2, Color::Blue, 0);
+    fig.set_grid(true, 0);
+    fig.set_x_label("Time [s]", 0);
+    fig.set_y_label("Amplitude", 0);
+    fig.set_caption("Damped oscillation", 0);
+
+    fig.xregion(2.0, 4.0, Color::Yellow, 0);
+    fig.yline(0.5, "Upper threshold", LineStyle::Dash, 1, Color::Red, 0);
+    fig.yline(-0.5, "Lower threshold", LineStyle::Dash, 1, Color::Red, 0);
+    fig.xline(
+        std::f64::consts::PI,
+        "t = pi",
+        LineStyle::Dot,
+        1,
+        Color::DarkGreen,
+        0,
+    );
+    fig.text(
+        0.05,
+        0.92,
+        "Drag me with the middle button",
+        CoordinateSystem::Axes,
+        Color::Black,
+        0,
+    );
+    fig.arrow(
+        (3.0, 0.8),
+        (0.8, 0.85),
+        CoordinateSystem::Data,
+        1,
+        Color::Black,
+        0,
+    );
+    fig.text(
+        3.05,
+        0.75,
+        "First peak",
+        CoordinateSystem::Data,
+        Color::Black,
+        0,
+    );
+
+    fig.end();
+    fig.show();
+
+    app.run().unwrap();
+}
@@ -0,0 +1,56 @@
+use fltk::{prelude::*, enums::*, app::*, draw::*};
+use fltk_plot::windows::figure_window::*;
+
+fn linspace(arr: &mut Vec<f64>, l: f64, r: f64, len: usize) {
+    let dx = (r - l) / (len - 1) as f64;
+
+    arr.reserve(len);
+
+    for i in 0..len {
+        arr.push(l + (i as f64 * dx));
+    }
+}
+
+fn arrayfun(input: &[f64], output: &mut Vec<f64>, fun: &dyn Fn(f64) -> f64) {
+    output.reserve(input.len());
+
+    for i in input.iter() {
+        output.push(fun(*i));
+    }
+}
+
+fn main() {
+    let app = App::default();
+
+    const N: usize = 100;
+    let mut t: Vec<f64> = Vec::with_capacity(N);
+    let mut x: Vec<f64> = Vec::with_capacity(N);
+    let mut y: Vec<f64> = Vec::with_capacity(N);
+
+    linspace(&mut t, 0.0, 2.0 * std::f64::consts::PI, N);
+    arrayfun(&t, &mut x, &f64::sin);
+    arrayfun(&t, &mut y, &f64::cos);
+
+    let mut fig = FigureWindow::new(640, 640, "Figure labels", 2, 1);
+    fig.plot(&t, &x, LineStyle::Solid, 2, Color::Red, 0);
+    fig.set_hold(true, 0);
+    fig.plot(&t, &y, LineStyle::Dash, 2, Color::Blue, 0);
+    fig.set_series_names(&["Channel A", "Channel B"], 0);
+    fig.plot(&t, &y, LineStyle::Dash, 2, Color::Blue, 1);
+    fig.set_series_names(&["Channel B"], 1);
+
+    fig.set_caption("Both channels", 0);
+    fig.set_caption("Channel B only", 1);
+    fig.set_grid(true, 0);
+    fig.set_grid(true, 1);
+
+    fig.set_super_title("Capture 1");
+    fig.set_super_x_label("Time [s]");
+    fig.set_super_y_label("Voltage [V]");
+    fig.set_figure_legend(true);
+
+    fig.end();
+    fig.show();
+
+    app.run().unwrap();
+}
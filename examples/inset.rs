@@ -0,0 +1,56 @@
+use fltk::{prelude::*, enums::*, app::*, draw::*};
+use fltk_plot::{widgets::widget::Limit, windows::figure_window::*};
+
+fn linspace(arr: &mut Vec<f64>, l: f64, r: f64, len: usize) {
+    let dx = (r - l) / (len - 1) as f64;
+
+    arr.reserve(len);
+
+    for i in 0..len {
+        arr.push(l + (i as f64 * dx));
+    }
+}
+
+fn arrayfun(input: &[f64], output: &mut Vec<f64>, fun: &dyn Fn(f64) -> f64) {
+    output.reserve(input.len());
+
+    for i in input.iter() {
+        output.push(fun(*i));
+    }
+}
+
+fn main() {
+    let app = App::default();
+
+    const N: usize = 1000;
+    let mut t: Vec<f64> = Vec::with_capacity(N);
+    let mut x: Vec<f64> = Vec::with_capacity(N);
+
+    linspace(&mut t, 0.0, 10.0, N);
+    arrayfun(&t, &mut x, &|t: f64| {
+        f64::sin(t) + 0.05 * f64::sin(40.0 * t)
+    });
+
+    let mut fig = FigureWindow::new(640, 480, "Inset", 1, 1);
+    fig.plot(&t, &x, LineStyle::Solid, 1, Color::Blue, 0);
+    fig.set_grid(true, 0);
+    fig.set_caption("Signal with ripple", 0);
+
+    // Zoomed detail view of the first peak in the top right corner.
+    if let Some(inset) = fig.add_inset(0, (0.6, 0.55, 0.38, 0.4)) {
+        fig.link_inset(
+            inset,
+            Limit {
+                x_left: 1.2,
+                x_right: 2.0,
+                y_left: 0.9,
+                y_right: 1.1,
+            },
+        );
+    }
+
+    fig.end();
+    fig.show();
+
+    app.run().unwrap();
+}
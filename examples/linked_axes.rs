@@ -0,0 +1,73 @@
+use fltk::{prelude::*, enums::*, app::*, draw::*};
+use fltk_plot::{widgets::axis_link::LinkAxis, windows::figure_window::*};
+
+fn linspace(arr: &mut Vec<f64>, l: f64, r: f64, len: usize) {
+    let dx = (r - l) / (len - 1) as f64;
+
+    arr.reserve(len);
+
+    for i in 0..len {
+        arr.push(l + (i as f64 * dx));
+    }
+}
+
+fn arrayfun(input: &[f64], output: &mut Vec<f64>, fun: &dyn Fn(f64) -> f64) {
+    output.reserve(input.len());
+
+    for i in input.iter() {
+        output.push(fun(*i));
+    }
+}
+
+fn main() {
+    let app = App::default();
+
+    const N: usize = 1000;
+    let mut t: Vec<f64> = Vec::with_capacity(N);
+    linspace(&mut t, 0.0, 1.0, N);
+
+    let mut fig = FigureWindow::new(800, 600, "Linked axes", 3, 1);
+
+    for (i, color) in [Color::Red, Color::DarkGreen, Color::Blue]
+        .iter()
+        .enumerate()
+    {
+        let mut x: Vec<f64> = Vec::with_capacity(N);
+        let f = 2.0 + 3.0 * i as f64;
+        arrayfun(&t, &mut x, &|t: f64| {
+            f64::sin(2.0 * std::f64::consts::PI * f * t)
+        });
+
+        fig.plot(&t, &x, LineStyle::Solid, 1, *color, i);
+        fig.set_caption(&format!("Channel {}", i + 1), i);
+        fig.set_grid(true, i);
+    }
+
+    // Zooming or panning any channel moves the time axis of all of them.
+    let group = fig.link_axes(&[0, 1, 2], LinkAxis::X);
+
+    // Show the value of every channel at the time under the mouse pointer.
+    fig.set_sync_cursor(true);
+
+    // Switch between zooming and panning without a middle mouse button.
+    fig.set_toolbar(true);
+
+    fig.end();
+    fig.show();
+
+    // The reference in a separate window stays aligned with the capture as well.
+    let mut reference: Vec<f64> = Vec::with_capacity(N);
+    arrayfun(&t, &mut reference, &|t: f64| {
+        f64::signum(f64::sin(4.0 * std::f64::consts::PI * t))
+    });
+
+    let mut fig2 = FigureWindow::new(800, 300, "Reference", 1, 1);
+    fig2.plot(&t, &reference, LineStyle::Solid, 1, Color::Black, 0);
+    fig2.set_caption("Reference", 0);
+    fig2.join_link_group(&group, 0);
+
+    fig2.end();
+    fig2.show();
+
+    app.run().unwrap();
+}
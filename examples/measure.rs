@@ -0,0 +1,59 @@
+use fltk::{prelude::*, enums::*, app::*, draw::*};
+use fltk_plot::windows::figure_window::*;
+
+fn linspace(arr: &mut Vec<f64>, l: f64, r: f64, len: usize) {
+    let dx = (r - l) / (len - 1) as f64;
+
+    arr.reserve(len);
+
+    for i in 0..len {
+        arr.push(l + (i as f64 * dx));
+    }
+}
+
+fn arrayfun(input: &[f64], output: &mut Vec<f64>, fun: &dyn Fn(f64) -> f64) {
+    output.reserve(input.len());
+
+    for i in input.iter() {
+        output.push(fun(*i));
+    }
+}
+
+fn main() {
+    let app = App::default();
+
+    const N: usize = 1000;
+    let mut t: Vec<f64> = Vec::with_capacity(N);
+    let mut x: Vec<f64> = Vec::with_capacity(N);
+    let mut y: Vec<f64> = Vec::with_capacity(N);
+
+    linspace(&mut t, 0.0, 0.01, N);
+    arrayfun(&t, &mut x, &|t: f64| {
+        f64::sin(2.0 * std::f64::consts::PI * 500.0 * t)
+    });
+    arrayfun(&t, &mut y, &|t: f64| {
+        0.5 * f64::cos(2.0 * std::f64::consts::PI * 500.0 * t)
+    });
+
+    let mut fig = FigureWindow::new(800, 600, "Measurement cursors", 1, 1);
+    fig.plot(&t, &x, LineStyle::Solid, 1, Color::Red, 0);
+    fig.set_hold(true, 0);
+    fig.plot(&t, &y, LineStyle::Solid, 1, Color::Blue, 0);
+    fig.set_caption("Drag the cursors to measure period and amplitude", 0);
+    fig.set_grid(true, 0);
+    fig.set_x_formatter(|t| format!("{:.2} ms", t * 1000.0), 0);
+
+    // Show the coordinates under the mouse pointer in the status area.
+    fig.set_crosshair(true);
+
+    fig.show_measure_cursors(true, true, 0);
+
+    // Statistics of both signals over one period.
+    fig.set_region_tool(true, 0);
+    fig.set_stats_region(0.002, 0.004, 0);
+
+    fig.end();
+    fig.show();
+
+    app.run().unwrap();
+}
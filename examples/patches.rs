@@ -0,0 +1,44 @@
+use fltk::{prelude::*, enums::*, app::*, draw::*};
+use fltk_plot::windows::figure_window::*;
+
+fn main() {
+    let app = App::default();
+
+    let x = [0.5, 1.5, 2.5, 3.5, 4.5, 5.5, 6.5];
+    let y = [1.2, 2.8, 2.1, 3.9, 3.0, 4.4, 3.6];
+
+    let mut fig = FigureWindow::new(640, 480, "Patches", 1, 1);
+    fig.plot(&x, &y, LineStyle::Solid, 2, Color::Blue, 0);
+    fig.set_grid(true, 0);
+    fig.set_caption("Detections with bounding boxes", 0);
+
+    fig.rectangle((1.0, 2.0), 2.0, 1.5, None, Some(Color::Red), 0);
+    fig.rectangle(
+        (4.0, 2.5),
+        2.0,
+        2.5,
+        Some(Color::from_rgb(220, 235, 255)),
+        Some(Color::Blue),
+        0,
+    );
+    fig.ellipse(
+        (5.5, 1.0),
+        1.5,
+        0.5,
+        Some(Color::from_rgb(255, 235, 200)),
+        None,
+        0,
+    );
+    fig.patch(
+        &[0.0, 1.0, 0.5],
+        &[4.0, 4.0, 5.0],
+        Some(Color::from_rgb(210, 245, 210)),
+        Some(Color::DarkGreen),
+        0,
+    );
+
+    fig.end();
+    fig.show();
+
+    app.run().unwrap();
+}
@@ -0,0 +1,52 @@
+use fltk::{prelude::*, enums::*, app::*, draw::*};
+use fltk_plot::windows::figure_window::*;
+
+fn linspace(arr: &mut Vec<f64>, l: f64, r: f64, len: usize) {
+    let dx = (r - l) / (len - 1) as f64;
+
+    arr.reserve(len);
+
+    for i in 0..len {
+        arr.push(l + (i as f64 * dx));
+    }
+}
+
+fn arrayfun(input: &[f64], output: &mut Vec<f64>, fun: &dyn Fn(f64) -> f64) {
+    output.reserve(input.len());
+
+    for i in input.iter() {
+        output.push(fun(*i));
+    }
+}
+
+fn main() {
+    let app = App::default();
+
+    const N: usize = 200;
+    let mut t: Vec<f64> = Vec::with_capacity(N);
+    let mut x: Vec<f64> = Vec::with_capacity(N);
+    let mut y: Vec<f64> = Vec::with_capacity(N);
+
+    linspace(&mut t, 0.0, 4.0 * std::f64::consts::PI, N);
+    arrayfun(&t, &mut x, &f64::sin);
+    arrayfun(&t, &mut y, &|t: f64| f64::sin(3.0 * t) / 3.0);
+
+    let mut fig = FigureWindow::new(800, 600, "Tiled layout", 2, 2);
+
+    // A wide plot across the top row, and two plots below it.
+    fig.set_span(0, 1, 2);
+    fig.set_row_ratios(&[2.0, 1.0]);
+    fig.set_gaps(10, 10);
+
+    fig.plot(&t, &x, LineStyle::Solid, 1, Color::Blue, 0);
+    fig.set_caption("Overview", 0);
+    fig.plot(&t, &y, LineStyle::Solid, 1, Color::Red, 2);
+    fig.set_caption("Harmonic", 2);
+    fig.stem(&t[..20], &x[..20], LineStyle::Solid, 1, Color::DarkGreen, 3);
+    fig.set_caption("First samples", 3);
+
+    fig.end();
+    fig.show();
+
+    app.run().unwrap();
+}
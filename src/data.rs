@@ -1 +1,2 @@
+pub mod patch_2d_data;
 pub mod plot_2d_data;
@@ -0,0 +1,167 @@
+use fltk::{enums::Color, draw::*};
+
+#[derive(Clone)]
+pub struct Patch2DData {
+    pub face_color: Option<Color>,
+    pub edge_color: Option<Color>,
+    pub style: LineStyle,
+    pub width: i32,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub x_min: f64,
+    pub y_min: f64,
+    pub x_max: f64,
+    pub y_max: f64,
+}
+
+#[allow(dead_code)]
+impl Patch2DData {
+    const ELLIPSE_VERTICES: usize = 72;
+
+    pub fn new(
+        x: &[f64],
+        y: &[f64],
+        face_color: Option<Color>,
+        edge_color: Option<Color>,
+        style: LineStyle,
+        width: i32,
+    ) -> Patch2DData {
+        let len = usize::min(x.len(), y.len());
+        let x = &x[..len];
+        let y = &y[..len];
+
+        let x_min = x.iter().cloned().fold(f64::INFINITY, f64::min);
+        let x_max = x.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let y_min = y.iter().cloned().fold(f64::INFINITY, f64::min);
+        let y_max = y.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        Patch2DData {
+            face_color,
+            edge_color,
+            style,
+            width,
+            x: x.to_vec(),
+            y: y.to_vec(),
+            x_min: if len > 0 { x_min } else { 0.0 },
+            y_min: if len > 0 { y_min } else { 0.0 },
+            x_max: if len > 0 { x_max } else { 0.0 },
+            y_max: if len > 0 { y_max } else { 0.0 },
+        }
+    }
+
+    // (x, y) is the bottom left corner of the rectangle.
+    pub fn rectangle(
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        face_color: Option<Color>,
+        edge_color: Option<Color>,
+    ) -> Patch2DData {
+        Patch2DData::new(
+            &[x, x + width, x + width, x],
+            &[y, y, y + height, y + height],
+            face_color,
+            edge_color,
+            LineStyle::Solid,
+            1,
+        )
+    }
+
+    // The ellipse is approximated by a polygon so that it scales correctly with both axes.
+    pub fn ellipse(
+        cx: f64,
+        cy: f64,
+        rx: f64,
+        ry: f64,
+        face_color: Option<Color>,
+        edge_color: Option<Color>,
+    ) -> Patch2DData {
+        let mut x = Vec::with_capacity(Patch2DData::ELLIPSE_VERTICES);
+        let mut y = Vec::with_capacity(Patch2DData::ELLIPSE_VERTICES);
+
+        for i in 0..Patch2DData::ELLIPSE_VERTICES {
+            let angle =
+                i as f64 * 2.0 * std::f64::consts::PI / Patch2DData::ELLIPSE_VERTICES as f64;
+            x.push(cx + rx * f64::cos(angle));
+            y.push(cy + ry * f64::sin(angle));
+        }
+
+        Patch2DData::new(&x, &y, face_color, edge_color, LineStyle::Solid, 1)
+    }
+
+    pub fn get_x_limit(&self) -> (f64, f64) {
+        (self.x_min, self.x_max)
+    }
+
+    pub fn get_y_limit(&self) -> (f64, f64) {
+        (self.y_min, self.y_max)
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_keeps_the_common_length() {
+        let p = Patch2DData::new(
+            &[0.0, 2.0, 1.0],
+            &[1.0, -1.0],
+            None,
+            None,
+            LineStyle::Solid,
+            1,
+        );
+
+        assert_eq!(p.len(), 2);
+        assert_eq!(p.get_x_limit(), (0.0, 2.0));
+        assert_eq!(p.get_y_limit(), (-1.0, 1.0));
+    }
+
+    #[test]
+    fn empty_patch_has_zero_extents() {
+        let p = Patch2DData::new(&[], &[], None, None, LineStyle::Solid, 1);
+
+        assert!(p.is_empty());
+        assert_eq!(p.get_x_limit(), (0.0, 0.0));
+        assert_eq!(p.get_y_limit(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn rectangle_from_bottom_left_corner() {
+        let p = Patch2DData::rectangle(1.0, 2.0, 3.0, 4.0, Some(Color::Red), None);
+
+        assert_eq!(p.len(), 4);
+        assert_eq!(p.get_x_limit(), (1.0, 4.0));
+        assert_eq!(p.get_y_limit(), (2.0, 6.0));
+        assert_eq!(p.face_color, Some(Color::Red));
+    }
+
+    #[test]
+    fn rectangle_with_negative_size() {
+        let p = Patch2DData::rectangle(1.0, 2.0, -3.0, -4.0, None, None);
+
+        assert_eq!(p.get_x_limit(), (-2.0, 1.0));
+        assert_eq!(p.get_y_limit(), (-2.0, 2.0));
+    }
+
+    #[test]
+    fn ellipse_extents() {
+        let p = Patch2DData::ellipse(1.0, -1.0, 2.0, 0.5, None, Some(Color::Black));
+        let (x_min, x_max) = p.get_x_limit();
+        let (y_min, y_max) = p.get_y_limit();
+
+        assert_eq!(p.len(), Patch2DData::ELLIPSE_VERTICES);
+        assert!(f64::abs(x_min + 1.0) < 1e-9 && f64::abs(x_max - 3.0) < 1e-9);
+        assert!(f64::abs(y_min + 1.5) < 1e-9 && f64::abs(y_max + 0.5) < 1e-9);
+    }
+}
@@ -1,7 +1,20 @@
 use fltk::{enums::Color, draw::*};
 
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RegionStatistics {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub rms: f64,
+    pub std_dev: f64,
+    pub peak_to_peak: f64,
+    pub integral: f64,
+    pub count: usize,
+}
+
 #[derive(Clone)]
 pub struct Plot2DData {
+    pub name: String,
     pub style: LineStyle,
     pub width: i32,
     pub color: Color,
@@ -12,12 +25,16 @@ pub struct Plot2DData {
     pub x_max: f64,
     pub y_max: f64,
     pub length: usize,
+    pub visible: bool,
+    // Extra text for each sample that is passed to the data tip formatter, empty if there is none.
+    pub metadata: Vec<String>,
 }
 
 #[allow(dead_code)]
 impl Plot2DData {
     pub fn new(length: usize, style: LineStyle, width: i32, color: Color) -> Plot2DData {
         Plot2DData {
+            name: String::new(),
             style,
             width,
             color,
@@ -28,6 +45,8 @@ impl Plot2DData {
             x_max: 0.0,
             y_max: 0.0,
             length,
+            visible: true,
+            metadata: Vec::new(),
         }
     }
 
@@ -52,6 +71,7 @@ impl Plot2DData {
         }
 
         Plot2DData {
+            name: String::new(),
             style,
             width,
             color,
@@ -62,6 +82,8 @@ impl Plot2DData {
             x_max,
             y_max,
             length: x.len(),
+            visible: true,
+            metadata: Vec::new(),
         }
     }
 
@@ -73,18 +95,67 @@ impl Plot2DData {
         (self.y_min, self.y_max)
     }
 
+    // Moving a sample that was on the edge of the extents inward shrinks them.
     pub fn set_value(&mut self, index: usize, nx: f64, ny: f64) {
         if index < self.length {
+            let (ox, oy) = (self.x[index], self.y[index]);
             self.x[index] = nx;
             self.y[index] = ny;
 
-            self.x_min = if self.x_min > nx { nx } else { self.x_min };
-            self.x_max = if self.x_max < nx { nx } else { self.x_max };
-            self.y_min = if self.y_min > ny { ny } else { self.y_min };
-            self.y_max = if self.y_max < ny { ny } else { self.y_max };
+            if ox == self.x_min || ox == self.x_max || oy == self.y_min || oy == self.y_max {
+                self.update_extents();
+            } else {
+                self.x_min = if self.x_min > nx { nx } else { self.x_min };
+                self.x_max = if self.x_max < nx { nx } else { self.x_max };
+                self.y_min = if self.y_min > ny { ny } else { self.y_min };
+                self.y_max = if self.y_max < ny { ny } else { self.y_max };
+            }
+        }
+    }
+
+    // Inserts a sample before index, or appends it if index is the length.
+    pub fn insert_value(&mut self, index: usize, nx: f64, ny: f64) {
+        if index <= self.length {
+            self.x.insert(index, nx);
+            self.y.insert(index, ny);
+            if !self.metadata.is_empty() {
+                self.metadata
+                    .insert(index.min(self.metadata.len()), String::new());
+            }
+            self.length += 1;
+            self.update_extents();
+        }
+    }
+
+    pub fn remove_value(&mut self, index: usize) -> Option<(f64, f64)> {
+        if index < self.length {
+            let value = (self.x.remove(index), self.y.remove(index));
+            if index < self.metadata.len() {
+                self.metadata.remove(index);
+            }
+            self.length -= 1;
+            self.update_extents();
+            Some(value)
+        } else {
+            None
         }
     }
 
+    // Unlike set_value, removing samples can also shrink the extents.
+    fn update_extents(&mut self) {
+        let extent = |v: &[f64]| {
+            v.iter()
+                .fold(None, |e: Option<(f64, f64)>, v| match e {
+                    Some((min, max)) => Some((f64::min(min, *v), f64::max(max, *v))),
+                    None => Some((*v, *v)),
+                })
+                .unwrap_or((0.0, 0.0))
+        };
+
+        (self.x_min, self.x_max) = extent(&self.x);
+        (self.y_min, self.y_max) = extent(&self.y);
+    }
+
     pub fn get_value(&self, index: usize) -> Option<(f64, f64)> {
         if index < self.length {
             Some((self.x[index], self.y[index]))
@@ -92,4 +163,228 @@ impl Plot2DData {
             None
         }
     }
+
+    pub fn get_metadata(&self, index: usize) -> Option<&str> {
+        self.metadata
+            .get(index)
+            .map(|m| m.as_str())
+            .filter(|m| !m.is_empty())
+    }
+
+    // Linearly interpolates y at x on the first line segment that contains x.
+    pub fn interpolate(&self, x: f64) -> Option<f64> {
+        for i in 1..self.length {
+            let (x0, y0) = (self.x[i - 1], self.y[i - 1]);
+            let (x1, y1) = (self.x[i], self.y[i]);
+
+            if (x0 <= x && x <= x1) || (x1 <= x && x <= x0) {
+                if x0 == x1 {
+                    return Some(y0);
+                }
+
+                return Some(y0 + (x - x0) / (x1 - x0) * (y1 - y0));
+            }
+        }
+
+        if self.length == 1 && self.x[0] == x {
+            Some(self.y[0])
+        } else {
+            None
+        }
+    }
+
+    // Statistics of the samples with x_left <= x <= x_right. The integral uses the
+    // trapezoidal rule and includes the interpolated values at both ends of the range.
+    pub fn statistics(&self, x_left: f64, x_right: f64) -> Option<RegionStatistics> {
+        let (x_left, x_right) = (f64::min(x_left, x_right), f64::max(x_left, x_right));
+        let inside: Vec<usize> = (0..self.length)
+            .filter(|&i| x_left <= self.x[i] && self.x[i] <= x_right)
+            .collect();
+
+        if inside.is_empty() {
+            return None;
+        }
+
+        let n = inside.len() as f64;
+        let mut stats = RegionStatistics {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            count: inside.len(),
+            ..RegionStatistics::default()
+        };
+
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+        for &i in inside.iter() {
+            let y = self.y[i];
+            stats.min = f64::min(stats.min, y);
+            stats.max = f64::max(stats.max, y);
+            sum += y;
+            sum_sq += y * y;
+        }
+
+        stats.mean = sum / n;
+        stats.rms = f64::sqrt(sum_sq / n);
+        stats.std_dev = f64::sqrt(f64::max(sum_sq / n - stats.mean * stats.mean, 0.0));
+        stats.peak_to_peak = stats.max - stats.min;
+
+        let mut points: Vec<(f64, f64)> = Vec::with_capacity(inside.len() + 2);
+        if let Some(y) = self.interpolate(x_left) {
+            points.push((x_left, y));
+        }
+        points.extend(inside.iter().map(|&i| (self.x[i], self.y[i])));
+        if let Some(y) = self.interpolate(x_right) {
+            points.push((x_right, y));
+        }
+
+        stats.integral = points
+            .windows(2)
+            .map(|p| (p[1].0 - p[0].0) * (p[0].1 + p[1].1) / 2.0)
+            .sum();
+
+        Some(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::close;
+
+    fn ramp() -> Plot2DData {
+        Plot2DData::new_xy(
+            &[0.0, 1.0, 2.0, 3.0, 4.0],
+            &[0.0, 1.0, 2.0, 3.0, 4.0],
+            LineStyle::Solid,
+            1,
+            Color::Black,
+        )
+    }
+
+    #[test]
+    fn statistics_of_full_range() {
+        let s = ramp().statistics(0.0, 4.0).unwrap();
+
+        assert_eq!(s.count, 5);
+        assert_eq!((s.min, s.max, s.peak_to_peak), (0.0, 4.0, 4.0));
+        assert!(close(s.mean, 2.0));
+        assert!(close(s.rms, f64::sqrt(6.0)));
+        assert!(close(s.std_dev, f64::sqrt(2.0)));
+        assert!(close(s.integral, 8.0));
+    }
+
+    #[test]
+    fn statistics_include_interpolated_ends() {
+        let s = ramp().statistics(0.5, 3.5).unwrap();
+
+        assert_eq!(s.count, 3);
+        assert!(close(s.integral, 6.0));
+    }
+
+    #[test]
+    fn statistics_with_reversed_bounds() {
+        let d = ramp();
+
+        assert_eq!(d.statistics(3.5, 0.5), d.statistics(0.5, 3.5));
+    }
+
+    #[test]
+    fn statistics_of_empty_range() {
+        assert_eq!(ramp().statistics(1.2, 1.8), None);
+        assert_eq!(ramp().statistics(5.0, 6.0), None);
+
+        let empty = Plot2DData::new_xy(&[], &[], LineStyle::Solid, 1, Color::Black);
+        assert_eq!(empty.statistics(0.0, 1.0), None);
+    }
+
+    #[test]
+    fn interpolate_between_samples() {
+        let d = Plot2DData::new_xy(&[0.0, 2.0], &[1.0, 5.0], LineStyle::Solid, 1, Color::Black);
+
+        assert_eq!(d.interpolate(1.0), Some(3.0));
+        assert_eq!(d.interpolate(0.0), Some(1.0));
+        assert_eq!(d.interpolate(2.0), Some(5.0));
+        assert_eq!(d.interpolate(-0.1), None);
+        assert_eq!(d.interpolate(2.1), None);
+    }
+
+    #[test]
+    fn interpolate_descending_and_single_sample() {
+        let d = Plot2DData::new_xy(&[2.0, 0.0], &[5.0, 1.0], LineStyle::Solid, 1, Color::Black);
+        assert_eq!(d.interpolate(1.0), Some(3.0));
+
+        let d = Plot2DData::new_xy(&[1.0], &[7.0], LineStyle::Solid, 1, Color::Black);
+        assert_eq!(d.interpolate(1.0), Some(7.0));
+        assert_eq!(d.interpolate(1.5), None);
+    }
+
+    #[test]
+    fn insert_value_updates_length_and_extents() {
+        let mut d = ramp();
+
+        d.insert_value(5, 5.0, -1.0);
+        assert_eq!(d.length, 6);
+        assert_eq!(d.get_value(5), Some((5.0, -1.0)));
+        assert_eq!((d.x_max, d.y_min), (5.0, -1.0));
+
+        d.insert_value(0, -1.0, 2.0);
+        assert_eq!(d.get_value(0), Some((-1.0, 2.0)));
+        assert_eq!(d.x_min, -1.0);
+    }
+
+    #[test]
+    fn insert_value_out_of_range_is_ignored() {
+        let mut d = ramp();
+
+        d.insert_value(7, 9.0, 9.0);
+        assert_eq!(d.length, 5);
+        assert_eq!(d.x_max, 4.0);
+    }
+
+    #[test]
+    fn remove_value_shrinks_extents() {
+        let mut d = ramp();
+
+        assert_eq!(d.remove_value(4), Some((4.0, 4.0)));
+        assert_eq!(d.length, 4);
+        assert_eq!((d.x_max, d.y_max), (3.0, 3.0));
+        assert_eq!(d.remove_value(4), None);
+    }
+
+    #[test]
+    fn remove_last_value_resets_extents() {
+        let mut d = Plot2DData::new_xy(&[3.0], &[4.0], LineStyle::Solid, 1, Color::Black);
+
+        assert_eq!(d.remove_value(0), Some((3.0, 4.0)));
+        assert_eq!(d.length, 0);
+        assert_eq!((d.get_x_limit(), d.get_y_limit()), ((0.0, 0.0), (0.0, 0.0)));
+    }
+
+    #[test]
+    fn set_value_moves_extents_both_ways() {
+        let mut d = ramp();
+
+        d.set_value(4, 2.5, 1.0);
+        assert_eq!((d.x_max, d.y_max), (3.0, 3.0));
+
+        d.set_value(0, -2.0, 9.0);
+        assert_eq!((d.x_min, d.y_max), (-2.0, 9.0));
+
+        d.set_value(10, 100.0, 100.0);
+        assert_eq!(d.x_max, 3.0);
+    }
+
+    #[test]
+    fn insert_and_remove_keep_metadata_aligned() {
+        let mut d = ramp();
+        d.metadata = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+
+        d.insert_value(1, 0.5, 0.5);
+        assert_eq!(d.get_metadata(1), None);
+        assert_eq!(d.get_metadata(2), Some("b"));
+
+        d.remove_value(0);
+        assert_eq!(d.metadata.len(), d.length);
+        assert_eq!(d.get_metadata(1), Some("b"));
+    }
 }
@@ -1,3 +1,6 @@
 pub mod data;
 pub mod widgets;
 pub mod windows;
+
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,43 @@
+// Fixtures shared by the unit tests of the crate.
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+
+use crate::widgets::widget::Limit;
+
+pub fn limit(x_left: f64, x_right: f64, y_left: f64, y_right: f64) -> Limit {
+    Limit {
+        x_left,
+        x_right,
+        y_left,
+        y_right,
+    }
+}
+
+pub fn close(a: f64, b: f64) -> bool {
+    f64::abs(a - b) < 1e-9
+}
+
+// FLTK is not thread safe and only takes timeouts on the thread that used it first, while
+// the test harness runs every test on a thread of its own. Tests that build widgets run
+// their body on one shared thread instead, a panic there fails the calling test.
+pub fn on_ui_thread<F: FnOnce() + Send + 'static>(f: F) {
+    type Job = (Box<dyn FnOnce() + Send>, mpsc::Sender<thread::Result<()>>);
+    static UI: OnceLock<Mutex<mpsc::Sender<Job>>> = OnceLock::new();
+
+    let ui = UI.get_or_init(|| {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        thread::spawn(move || {
+            for (job, done) in queue {
+                done.send(panic::catch_unwind(AssertUnwindSafe(job))).ok();
+            }
+        });
+        Mutex::new(jobs)
+    });
+
+    let (done, result) = mpsc::channel();
+    ui.lock().unwrap().send((Box::new(f), done)).unwrap();
+    if let Err(e) = result.recv().unwrap() {
+        panic::resume_unwind(e);
+    }
+}
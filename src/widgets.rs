@@ -1,5 +1,11 @@
+pub mod annotation;
+pub mod axis_link;
+pub mod cursor;
+pub mod event;
 pub mod graph_2d_widget;
 pub mod graph_widget;
+pub mod interaction;
 pub mod plot_2d_widget;
 pub mod stem_2d_widget;
+pub mod tip_layout;
 pub mod widget;
@@ -0,0 +1,157 @@
+use fltk::{enums::Color, draw::*};
+
+use crate::widgets::widget::AxisScale;
+
+// Coordinate system an annotation is anchored in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CoordinateSystem {
+    // Same units as the plotted data, so the annotation follows zoom and pan.
+    Data,
+    // Normalized to the axes box: (0, 0) is the bottom left and (1, 1) the top right corner.
+    Axes,
+}
+
+#[derive(Clone, Debug)]
+pub enum Annotation {
+    Text {
+        x: f64,
+        y: f64,
+        coords: CoordinateSystem,
+        text: String,
+        color: Color,
+    },
+    Arrow {
+        x0: f64,
+        y0: f64,
+        x1: f64,
+        y1: f64,
+        coords: CoordinateSystem,
+        width: i32,
+        color: Color,
+    },
+    XLine {
+        x: f64,
+        label: String,
+        style: LineStyle,
+        width: i32,
+        color: Color,
+    },
+    YLine {
+        y: f64,
+        label: String,
+        style: LineStyle,
+        width: i32,
+        color: Color,
+    },
+    XRegion {
+        x_left: f64,
+        x_right: f64,
+        color: Color,
+    },
+    YRegion {
+        y_left: f64,
+        y_right: f64,
+        color: Color,
+    },
+}
+
+#[allow(dead_code)]
+impl Annotation {
+    // (dx, dy) is the delta along the scaled axes, (ax, ay) the same delta in axes units. Data
+    // coordinates are moved on the axes, so a drag on a log axis multiplies them.
+    pub fn translate(
+        &mut self,
+        dx: f64,
+        dy: f64,
+        ax: f64,
+        ay: f64,
+        scales: (AxisScale, AxisScale),
+    ) {
+        let (x_scale, y_scale) = scales;
+        let move_x = |v: &mut f64, coords: &CoordinateSystem| match coords {
+            CoordinateSystem::Data => *v = x_scale.inverse(x_scale.forward(*v) + dx),
+            CoordinateSystem::Axes => *v += ax,
+        };
+        let move_y = |v: &mut f64, coords: &CoordinateSystem| match coords {
+            CoordinateSystem::Data => *v = y_scale.inverse(y_scale.forward(*v) + dy),
+            CoordinateSystem::Axes => *v += ay,
+        };
+
+        match self {
+            Annotation::Text { x, y, coords, .. } => {
+                move_x(x, coords);
+                move_y(y, coords);
+            }
+            Annotation::Arrow {
+                x0,
+                y0,
+                x1,
+                y1,
+                coords,
+                ..
+            } => {
+                move_x(x0, coords);
+                move_y(y0, coords);
+                move_x(x1, coords);
+                move_y(y1, coords);
+            }
+            Annotation::XLine { x, .. } => move_x(x, &CoordinateSystem::Data),
+            Annotation::YLine { y, .. } => move_y(y, &CoordinateSystem::Data),
+            Annotation::XRegion {
+                x_left, x_right, ..
+            } => {
+                move_x(x_left, &CoordinateSystem::Data);
+                move_x(x_right, &CoordinateSystem::Data);
+            }
+            Annotation::YRegion {
+                y_left, y_right, ..
+            } => {
+                move_y(y_left, &CoordinateSystem::Data);
+                move_y(y_right, &CoordinateSystem::Data);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x_line(x: f64) -> Annotation {
+        Annotation::XLine {
+            x,
+            label: String::new(),
+            style: LineStyle::Solid,
+            width: 1,
+            color: Color::Black,
+        }
+    }
+
+    #[test]
+    fn translate_moves_data_coordinates_along_log_axes() {
+        let mut line = x_line(10.0);
+        line.translate(1.0, 0.0, 0.5, 0.0, (AxisScale::Log, AxisScale::Linear));
+
+        match line {
+            Annotation::XLine { x, .. } => assert!((x - 100.0).abs() < 1e-9),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn translate_moves_axes_coordinates_by_the_axes_delta() {
+        let mut text = Annotation::Text {
+            x: 0.25,
+            y: 0.5,
+            coords: CoordinateSystem::Axes,
+            text: String::new(),
+            color: Color::Black,
+        };
+        text.translate(1.0, 1.0, 0.5, -0.25, (AxisScale::Log, AxisScale::Log));
+
+        match text {
+            Annotation::Text { x, y, .. } => assert_eq!((x, y), (0.75, 0.25)),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use crate::widgets::widget::Limit;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LinkAxis {
+    X,
+    Y,
+    XY,
+}
+
+// A set of graphs whose view limits are kept in sync along one or both axes. Members can
+// live in different figure windows. The group only holds weak references to their limits,
+// but the event handler of a graph keeps its limits alive as long as the widget exists. So
+// a graph leaves the group when its window is hidden, and joins it again when the window is
+// shown, see Graph2DWidget::set_linked.
+#[derive(Clone, Debug)]
+pub struct AxisLinkGroup {
+    pub axis: LinkAxis,
+    pub members: Rc<RefCell<Vec<Weak<RefCell<Limit>>>>>,
+}
+
+#[allow(dead_code)]
+impl AxisLinkGroup {
+    pub fn new(axis: LinkAxis) -> AxisLinkGroup {
+        AxisLinkGroup {
+            axis,
+            members: Rc::from(RefCell::from(Vec::new())),
+        }
+    }
+
+    // Returns false if the limits were in the group already.
+    pub fn add(&self, limit: &Rc<RefCell<Limit>>) -> bool {
+        let mut members = self.members.borrow_mut();
+
+        members.retain(|m| m.strong_count() > 0);
+        if members
+            .iter()
+            .any(|m| std::ptr::eq(m.as_ptr(), Rc::as_ptr(limit)))
+        {
+            return false;
+        }
+
+        members.push(Rc::downgrade(limit));
+        true
+    }
+
+    pub fn remove(&self, limit: &Rc<RefCell<Limit>>) {
+        self.members
+            .borrow_mut()
+            .retain(|m| m.strong_count() > 0 && !std::ptr::eq(m.as_ptr(), Rc::as_ptr(limit)));
+    }
+
+    fn copy(&self, src: &Limit, dst: &mut Limit) {
+        if self.axis != LinkAxis::Y {
+            dst.x_left = src.x_left;
+            dst.x_right = src.x_right;
+        }
+        if self.axis != LinkAxis::X {
+            dst.y_left = src.y_left;
+            dst.y_right = src.y_right;
+        }
+    }
+
+    // Copies the linked range of source to every other member of the group.
+    pub fn propagate(&self, source: &Rc<RefCell<Limit>>) {
+        let src = *source.borrow();
+
+        for member in self.members.borrow().iter().filter_map(|m| m.upgrade()) {
+            if !Rc::ptr_eq(&member, source) {
+                self.copy(&src, &mut member.borrow_mut());
+            }
+        }
+    }
+
+    // Copies the linked range of the first other member to target, so that a graph
+    // joining the group lines up with the ones already in it.
+    pub fn adopt(&self, target: &Rc<RefCell<Limit>>) {
+        let src = self
+            .members
+            .borrow()
+            .iter()
+            .filter_map(|m| m.upgrade())
+            .find(|m| !Rc::ptr_eq(m, target))
+            .map(|m| *m.borrow());
+
+        if let Some(src) = src {
+            self.copy(&src, &mut target.borrow_mut());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::limit;
+
+    fn member(x_left: f64, x_right: f64, y_left: f64, y_right: f64) -> Rc<RefCell<Limit>> {
+        Rc::new(RefCell::new(limit(x_left, x_right, y_left, y_right)))
+    }
+
+    #[test]
+    fn propagate_x_only() {
+        let group = AxisLinkGroup::new(LinkAxis::X);
+        let a = member(0.0, 1.0, 0.0, 1.0);
+        let b = member(5.0, 6.0, 5.0, 6.0);
+        group.add(&a);
+        group.add(&b);
+
+        *a.borrow_mut() = limit(2.0, 3.0, 2.0, 3.0);
+        group.propagate(&a);
+
+        assert_eq!(*b.borrow(), limit(2.0, 3.0, 5.0, 6.0));
+    }
+
+    #[test]
+    fn propagate_both_axes() {
+        let group = AxisLinkGroup::new(LinkAxis::XY);
+        let a = member(0.0, 1.0, 2.0, 3.0);
+        let b = member(5.0, 6.0, 5.0, 6.0);
+        group.add(&a);
+        group.add(&b);
+
+        group.propagate(&a);
+
+        assert_eq!(*b.borrow(), *a.borrow());
+    }
+
+    #[test]
+    fn adopt_takes_the_linked_axis_of_a_member() {
+        let group = AxisLinkGroup::new(LinkAxis::Y);
+        let a = member(0.0, 1.0, 2.0, 3.0);
+        let b = member(5.0, 6.0, 5.0, 6.0);
+        group.add(&a);
+        group.add(&b);
+
+        group.adopt(&b);
+
+        assert_eq!(*b.borrow(), limit(5.0, 6.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn adding_twice_keeps_one_member() {
+        let group = AxisLinkGroup::new(LinkAxis::XY);
+        let a = member(0.0, 1.0, 0.0, 1.0);
+        group.add(&a);
+        group.add(&a);
+
+        assert_eq!(group.members.borrow().len(), 1);
+    }
+
+    #[test]
+    fn dropped_members_leave_the_group() {
+        let group = AxisLinkGroup::new(LinkAxis::XY);
+        let a = member(0.0, 1.0, 0.0, 1.0);
+        group.add(&a);
+        group.add(&member(5.0, 6.0, 5.0, 6.0));
+
+        // The second limit is gone already, the next add clears it out.
+        group.propagate(&a);
+        group.add(&a);
+
+        assert_eq!(group.members.borrow().len(), 1);
+    }
+
+    #[test]
+    fn removed_members_are_not_updated() {
+        let group = AxisLinkGroup::new(LinkAxis::XY);
+        let a = member(0.0, 1.0, 0.0, 1.0);
+        let b = member(5.0, 6.0, 5.0, 6.0);
+        group.add(&a);
+        group.add(&b);
+
+        group.remove(&b);
+        group.propagate(&a);
+
+        assert_eq!(group.members.borrow().len(), 1);
+        assert_eq!(*b.borrow(), limit(5.0, 6.0, 5.0, 6.0));
+        assert!(group.add(&b));
+        assert!(!group.add(&b));
+    }
+
+    #[test]
+    fn adopt_without_other_members_keeps_the_limit() {
+        let group = AxisLinkGroup::new(LinkAxis::XY);
+        let a = member(0.0, 1.0, 0.0, 1.0);
+        group.add(&a);
+
+        group.adopt(&a);
+
+        assert_eq!(*a.borrow(), limit(0.0, 1.0, 0.0, 1.0));
+    }
+}
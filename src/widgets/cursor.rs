@@ -0,0 +1,48 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Cursor position shared by all graphs that show the same vertical cursor line.
+#[derive(Clone, Debug, Default)]
+pub struct SyncCursor {
+    pub x: Rc<RefCell<Option<f64>>>,
+}
+
+#[allow(dead_code)]
+impl SyncCursor {
+    pub fn new() -> SyncCursor {
+        SyncCursor::default()
+    }
+}
+
+// Text shown in the status area of a figure window, written by the graph under the
+// mouse pointer while the crosshair is on.
+#[derive(Clone, Debug, Default)]
+pub struct StatusLine {
+    pub text: Rc<RefCell<String>>,
+}
+
+#[allow(dead_code)]
+impl StatusLine {
+    pub fn new() -> StatusLine {
+        StatusLine::default()
+    }
+}
+
+// Positions of the measurement cursors, in data coordinates. The horizontal cursors
+// y1 and y2 are only shown and draggable if horizontal is set.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MeasureCursors {
+    pub x1: f64,
+    pub x2: f64,
+    pub y1: f64,
+    pub y2: f64,
+    pub horizontal: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CursorLine {
+    X1,
+    X2,
+    Y1,
+    Y2,
+}
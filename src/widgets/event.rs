@@ -0,0 +1,63 @@
+use std::rc::Rc;
+
+use crate::widgets::widget::{DataTip, Limit};
+
+// What happened in a subplot. Positions are in data coordinates, tips and samples are
+// given by their index.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PlotEvent {
+    // The view was zoomed, panned or reset. Pans and box zooms are reported once the
+    // mouse button is released, wheel steps one by one.
+    ViewChanged(Limit),
+    DataTipAdded {
+        index: usize,
+        tip: DataTip,
+    },
+    // The tip moved to another point of its series.
+    DataTipMoved {
+        index: usize,
+        tip: DataTip,
+    },
+    DataTipRemoved {
+        index: usize,
+        tip: DataTip,
+    },
+    // The button bound to MouseAction::Tool was clicked on a sample of a series.
+    PointClicked {
+        series: usize,
+        index: usize,
+        x: f64,
+        y: f64,
+    },
+    // An x-range was selected with the region tool.
+    SelectionMade {
+        x_left: f64,
+        x_right: f64,
+    },
+    // The mouse moved over the plot area.
+    MouseMoved {
+        x: f64,
+        y: f64,
+    },
+}
+
+pub type EventCallback = Rc<dyn Fn(&PlotEvent)>;
+
+pub type EventHandler = dyn Fn(usize, &PlotEvent);
+
+// The callback of a whole figure, which also gets the index of the subplot.
+#[derive(Clone)]
+pub struct FigureEventCallback(pub Rc<EventHandler>);
+
+impl FigureEventCallback {
+    pub fn for_subplot(&self, subplot: usize) -> EventCallback {
+        let callback = self.0.clone();
+        Rc::new(move |event| callback(subplot, event))
+    }
+}
+
+impl std::fmt::Debug for FigureEventCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FigureEventCallback")
+    }
+}
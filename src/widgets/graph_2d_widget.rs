@@ -1,12 +1,24 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Write;
 use std::ops::{Deref, DerefMut};
+use std::path::Path;
 use std::rc::Rc;
 
-use fltk::app::MouseWheel;
-use fltk::{prelude::*, enums::*, draw::*};
+use fltk::{prelude::*, enums::*, dialog::*, draw::*, menu::*, surface::*};
 
-use crate::data::plot_2d_data::*;
-use crate::widgets::{graph_widget::*, widget::*};
+use crate::data::{patch_2d_data::*, plot_2d_data::*};
+use crate::widgets::{
+    annotation::*, axis_link::*, cursor::*, event::*, graph_widget::*, interaction::*,
+    tip_layout::*, widget::*,
+};
+use crate::windows::property_dialog::show_property_dialog;
+
+pub type AxisFormatter = Rc<dyn Fn(f64) -> String>;
+// Receives the index and the new data of a series that was edited with the mouse.
+pub type DataCallback = Rc<dyn Fn(usize, &Plot2DData)>;
+// Builds the label of a data tip, which may have several lines.
+pub type TipFormatter = Rc<dyn Fn(&TipInfo) -> String>;
 
 #[derive(Clone)]
 pub struct Graph2DWidget {
@@ -14,13 +26,57 @@ pub struct Graph2DWidget {
     pub data: Rc<RefCell<Vec<Option<Plot2DData>>>>,
     pub data_tips: Rc<RefCell<Vec<DataTip>>>,
     pub closest_data_tip: Rc<RefCell<Option<usize>>>,
+    pub patches: Rc<RefCell<Vec<Patch2DData>>>,
+    pub annotations: Rc<RefCell<Vec<Annotation>>>,
+    pub closest_annotation: Rc<RefCell<Option<usize>>>,
+    pub inset_marks: Rc<RefCell<Vec<Rc<RefCell<Limit>>>>>,
+    pub axis_links: Rc<RefCell<Vec<AxisLinkGroup>>>,
+    pub sync_cursor: Rc<RefCell<Option<SyncCursor>>>,
+    pub measure_cursors: Rc<RefCell<Option<MeasureCursors>>>,
+    pub dragged_cursor: Rc<RefCell<Option<CursorLine>>>,
+    pub region_tool: Rc<RefCell<bool>>,
+    pub selecting_region: Rc<RefCell<bool>>,
+    pub stats_region: Rc<RefCell<Option<(f64, f64)>>>,
+    pub x_formatter: Rc<RefCell<Option<AxisFormatter>>>,
+    pub y_formatter: Rc<RefCell<Option<AxisFormatter>>>,
+    pub crosshair: Rc<RefCell<Option<StatusLine>>>,
+    pub pointer: Rc<RefCell<Option<(i32, i32)>>>,
+    pub mode: Rc<RefCell<InteractionMode>>,
+    pub panning: Rc<RefCell<bool>>,
+    pub edited_point: Rc<RefCell<Option<(usize, usize)>>>,
+    pub history: Rc<RefCell<Vec<Limit>>>,
+    pub history_pos: Rc<RefCell<usize>>,
+    pub view_start: Rc<RefCell<Option<Limit>>>,
+    pub wheel_start: Rc<RefCell<Option<Limit>>>,
+    pub wheel_timer: Rc<RefCell<Option<fltk::app::TimeoutHandle>>>,
+    pub zoom_axis: Rc<RefCell<ZoomAxis>>,
+    pub pan_axis: Rc<RefCell<ZoomAxis>>,
+    pub wheel: Rc<RefCell<WheelConfig>>,
+    pub selected_tip: Rc<RefCell<Option<usize>>>,
+    pub tip_style: Rc<RefCell<TipStyle>>,
+    pub tip_formatters: Rc<RefCell<HashMap<usize, TipFormatter>>>,
+    pub tip_snap: Rc<RefCell<TipSnap>>,
+    pub bindings: Rc<RefCell<Vec<MouseBinding>>>,
+    pub drag_action: Rc<RefCell<MouseAction>>,
+    pub click_action: Rc<RefCell<MouseAction>>,
+    pub press_pos: Rc<RefCell<(i32, i32)>>,
+    pub margins: Rc<RefCell<(i32, i32, i32, i32)>>,
+    pub x_scale: Rc<RefCell<AxisScale>>,
+    pub y_scale: Rc<RefCell<AxisScale>>,
+    pub legend: Rc<RefCell<bool>>,
+    pub edit: Rc<RefCell<EditConfig>>,
+    pub data_callback: Rc<RefCell<Option<DataCallback>>>,
+    pub event_callback: Rc<RefCell<Option<EventCallback>>>,
 }
 
 #[allow(dead_code)]
 impl Graph2DWidget {
-    const LEFT_BUTTON: i32 = 1;
-    const RIGHT_BUTTON: i32 = 3;
-    const MIDDLE_BUTTON: i32 = 2;
+    const LABEL_SIZE: i32 = 12;
+    const TICK_SIZE: i32 = 10;
+    const TEXT_SPC: i32 = 4;
+    const TIP_PADDING: i32 = 3;
+    // Seconds without wheel events after which a wheel gesture ends.
+    const WHEEL_IDLE: f64 = 0.4;
 
     pub fn new(x: i32, y: i32, width: i32, height: i32, caption: &str) -> Graph2DWidget {
         let mut x = Graph2DWidget {
@@ -28,444 +84,3737 @@ impl Graph2DWidget {
             data: Rc::from(RefCell::from(Vec::new())),
             data_tips: Rc::from(RefCell::from(Vec::new())),
             closest_data_tip: Rc::from(RefCell::from(None)),
+            patches: Rc::from(RefCell::from(Vec::new())),
+            annotations: Rc::from(RefCell::from(Vec::new())),
+            closest_annotation: Rc::from(RefCell::from(None)),
+            inset_marks: Rc::from(RefCell::from(Vec::new())),
+            axis_links: Rc::from(RefCell::from(Vec::new())),
+            sync_cursor: Rc::from(RefCell::from(None)),
+            measure_cursors: Rc::from(RefCell::from(None)),
+            dragged_cursor: Rc::from(RefCell::from(None)),
+            region_tool: Rc::from(RefCell::from(false)),
+            selecting_region: Rc::from(RefCell::from(false)),
+            stats_region: Rc::from(RefCell::from(None)),
+            x_formatter: Rc::from(RefCell::from(None)),
+            y_formatter: Rc::from(RefCell::from(None)),
+            crosshair: Rc::from(RefCell::from(None)),
+            pointer: Rc::from(RefCell::from(None)),
+            mode: Rc::from(RefCell::from(InteractionMode::default())),
+            panning: Rc::from(RefCell::from(false)),
+            edited_point: Rc::from(RefCell::from(None)),
+            history: Rc::from(RefCell::from(Vec::new())),
+            history_pos: Rc::from(RefCell::from(0)),
+            view_start: Rc::from(RefCell::from(None)),
+            wheel_start: Rc::from(RefCell::from(None)),
+            wheel_timer: Rc::from(RefCell::from(None)),
+            zoom_axis: Rc::from(RefCell::from(ZoomAxis::Both)),
+            pan_axis: Rc::from(RefCell::from(ZoomAxis::Both)),
+            wheel: Rc::from(RefCell::from(WheelConfig::default())),
+            selected_tip: Rc::from(RefCell::from(None)),
+            tip_style: Rc::from(RefCell::from(TipStyle::default())),
+            tip_formatters: Rc::from(RefCell::from(HashMap::new())),
+            tip_snap: Rc::from(RefCell::from(TipSnap::default())),
+            bindings: Rc::from(RefCell::from(MouseBinding::defaults())),
+            drag_action: Rc::from(RefCell::from(MouseAction::None)),
+            click_action: Rc::from(RefCell::from(MouseAction::None)),
+            press_pos: Rc::from(RefCell::from((0, 0))),
+            // Until the first draw measures the labels, the font sizes stand in for them.
+            margins: Rc::from(RefCell::from(Graph2DWidget::margins(
+                Graph2DWidget::LABEL_SIZE,
+                Graph2DWidget::TICK_SIZE,
+                0,
+                !caption.is_empty(),
+                false,
+                false,
+            ))),
+            x_scale: Rc::from(RefCell::from(AxisScale::Linear)),
+            y_scale: Rc::from(RefCell::from(AxisScale::Linear)),
+            legend: Rc::from(RefCell::from(false)),
+            edit: Rc::from(RefCell::from(EditConfig::default())),
+            data_callback: Rc::from(RefCell::from(None)),
+            event_callback: Rc::from(RefCell::from(None)),
         };
 
         x.handle();
         x
     }
 
-    pub fn get_closest_point(
-        data: &[Option<Plot2DData>],
-        mx: i32,
-        my: i32,
-        width: f64,
-        height: f64,
-        wid_width: f64,
-        wid_height: f64,
-        wid_x: f64,
-        wid_y: f64,
-        limit_c: &Limit,
-    ) -> Option<DataTip> {
-        let mut m_dist = wid_width * wid_width + wid_height * wid_height;
-
-        let mut tip = DataTip::default();
-
-        for (j, d) in data.iter().enumerate() {
-            if d.is_some() {
-                let d = d.as_ref().unwrap();
+    // Grows the initial limits to include the given extents, or replaces them if the
+    // graph does not contain anything yet.
+    pub fn update_limit(&mut self, x_min: f64, x_max: f64, y_min: f64, y_max: f64) {
+        let limit = if self.data.borrow().is_empty() && self.patches.borrow().is_empty() {
+            Limit {
+                x_left: x_min,
+                x_right: x_max,
+                y_left: y_min,
+                y_right: y_max,
+            }
+        } else {
+            Limit {
+                x_left: f64::min(self.limit.borrow().x_left, x_min),
+                x_right: f64::max(self.limit.borrow().x_right, x_max),
+                y_left: f64::min(self.limit.borrow().y_left, y_min),
+                y_right: f64::max(self.limit.borrow().y_right, y_max),
+            }
+        };
 
-                for i in 0..d.length {
-                    if let Some((mut px, mut py)) = d.get_value(i) {
-                        // Scale and shift
-                        px = ((px - limit_c.x_left) / width) * wid_width + wid_x;
-                        py = wid_height - ((py - limit_c.y_left) / height) * wid_height + wid_y;
-
-                        let dist = f64::max(f64::abs(px - mx as f64), f64::abs(py - my as f64));
-                        if dist < m_dist {
-                            if let Some((px, py)) = d.get_value(i) {
-                                m_dist = dist;
-                                tip.x = px;
-                                tip.y = py;
-                                tip.plot_idx = j;
-                            }
-                        }
-                    };
+        *self.limit.borrow_mut() = limit;
+        *self.limit_c.borrow_mut() = limit;
+    }
+
+    // Unlike put_data, patches never clear the graph, so they can be added on top of
+    // existing plots regardless of the hold state.
+    pub fn put_patch(&mut self, patch: Patch2DData) {
+        if patch.is_empty() {
+            return;
+        }
+
+        let (x_min, x_max) = patch.get_x_limit();
+        let (y_min, y_max) = patch.get_y_limit();
+
+        self.update_limit(x_min, x_max, y_min, y_max);
+        self.patches.borrow_mut().push(patch);
+
+        fltk::app::redraw();
+    }
+
+    pub fn clear_patches(&mut self) {
+        self.patches.borrow_mut().clear();
+        fltk::app::redraw();
+    }
+
+    pub fn draw_patches(&self) {
+        let area = self.plot_area();
+        let limit_c = *self.limit_c.borrow();
+        let scales = self.scales();
+
+        for patch in self.patches.borrow().iter() {
+            if let Some(color) = patch.face_color {
+                set_draw_color(color);
+                begin_complex_polygon();
+                for i in 0..patch.len() {
+                    let (px, py) =
+                        Graph2DWidget::map_to_pixel(area, &limit_c, scales, patch.x[i], patch.y[i]);
+                    vertex(px, py);
+                }
+                end_complex_polygon();
+            }
+
+            if let Some(color) = patch.edge_color {
+                set_draw_color(color);
+                set_line_style(patch.style, patch.width);
+                begin_loop();
+                for i in 0..patch.len() {
+                    let (px, py) =
+                        Graph2DWidget::map_to_pixel(area, &limit_c, scales, patch.x[i], patch.y[i]);
+                    vertex(px, py);
                 }
+                end_loop();
             }
         }
 
-        if m_dist <= 10.0 {
-            Some(tip)
-        } else {
-            None
+        set_line_style(LineStyle::Solid, 1);
+    }
+
+    // Names the series in the order they were added, which is what shows up in legends.
+    pub fn set_series_names(&mut self, names: &[&str]) {
+        for (d, name) in self.data.borrow_mut().iter_mut().zip(names.iter()) {
+            if let Some(d) = d {
+                d.name = name.to_string();
+            }
         }
+
+        fltk::app::redraw();
     }
 
-    pub fn get_closest_datatip(
-        data_tips: Rc<RefCell<Vec<DataTip>>>,
-        mx: i32,
-        my: i32,
-        width: f64,
-        height: f64,
-        wid_width: f64,
-        wid_height: f64,
-        wid_x: f64,
-        wid_y: f64,
-        limit_c: &Limit,
-    ) -> Option<usize> {
-        let mut closest: Option<usize> = None;
+    pub fn tick_label(value: f64) -> String {
+        format!("{:.2}", value)
+    }
 
-        let height = if height != 0.0 { height } else { 1.0 };
+    // Tick labels and coordinate readouts use the axis formatters, if set.
+    pub fn set_formatters(&mut self, x: Option<AxisFormatter>, y: Option<AxisFormatter>) {
+        *self.x_formatter.borrow_mut() = x;
+        *self.y_formatter.borrow_mut() = y;
+        fltk::app::redraw();
+    }
 
-        for (i, c) in data_tips.borrow().iter().enumerate() {
-            let px = ((c.x - limit_c.x_left) / width) * wid_width + wid_x;
-            let py = wid_height - ((c.y - limit_c.y_left) / height) * wid_height + wid_y;
-            let distance = i32::max(i32::abs(px as i32 - mx), i32::abs(py as i32 - my));
+    pub fn format_x(&self, value: f64) -> String {
+        match self.x_formatter.borrow().as_ref() {
+            Some(f) => f(value),
+            None => Graph2DWidget::tick_label(value),
+        }
+    }
 
-            if distance < 15 {
-                closest = Some(i);
-                break;
-            }
+    pub fn format_y(&self, value: f64) -> String {
+        match self.y_formatter.borrow().as_ref() {
+            Some(f) => f(value),
+            None => Graph2DWidget::tick_label(value),
         }
+    }
 
-        closest
+    // Returns n values that divide the range into n + 1 equal parts.
+    pub fn ticks(left: f64, right: f64, n: i32) -> Vec<f64> {
+        (1..=n)
+            .map(|i| i as f64 * (right - left) / (n as f64 + 1.0) + left)
+            .collect()
     }
-}
 
-impl MyWidget for Graph2DWidget {
-    fn put_data(&mut self, x: &[f64], y: &[f64], style: LineStyle, width: i32, color: Color) {
-        let data = Plot2DData::new_xy(x, y, style, width, color);
+    // Log axes that span at least a decade get their ticks on powers of ten, otherwise the
+    // ticks divide the axis into equal parts.
+    pub fn axis_ticks(scale: AxisScale, left: f64, right: f64, n: i32) -> Vec<f64> {
+        let (l, r) = (scale.forward(left), scale.forward(right));
 
-        if !*self.hold.borrow() {
-            self.data.borrow_mut().clear();
+        if !l.is_finite() || !r.is_finite() {
+            Vec::new()
+        } else if scale == AxisScale::Log && r - l >= 1.0 {
+            let step = f64::max(((r - l) / (n as f64 + 1.0)).ceil(), 1.0) as usize;
+
+            (l.ceil() as i32..=r.floor() as i32)
+                .step_by(step)
+                .map(|e| 10f64.powi(e))
+                .collect()
+        } else {
+            Graph2DWidget::ticks(l, r, n)
+                .into_iter()
+                .map(|v| scale.inverse(v))
+                .collect()
         }
+    }
 
-        let (x_min, x_max) = data.get_x_limit();
-        let (y_min, y_max) = data.get_y_limit();
+    pub fn x_ticks(&self) -> Vec<f64> {
+        let limit_c = *self.limit_c.borrow();
 
-        if self.data.borrow().is_empty() {
-            *self.limit.borrow_mut() = Limit {
-                x_left: x_min,
-                x_right: x_max,
-                y_left: y_min,
-                y_right: y_max,
-            };
-            *self.limit_c.borrow_mut() = Limit {
-                x_left: x_min,
-                x_right: x_max,
-                y_left: y_min,
-                y_right: y_max,
-            };
-        } else {
-            let tmp_x_min = f64::min(self.limit.borrow().x_left, x_min);
-            let tmp_x_max = f64::max(self.limit.borrow().x_right, x_max);
-            let tmp_y_min = f64::min(self.limit.borrow().y_left, y_min);
-            let tmp_y_max = f64::max(self.limit.borrow().y_right, y_max);
-
-            *self.limit.borrow_mut() = Limit {
-                x_left: tmp_x_min,
-                x_right: tmp_x_max,
-                y_left: tmp_y_min,
-                y_right: tmp_y_max,
-            };
-            *self.limit_c.borrow_mut() = Limit {
-                x_left: tmp_x_min,
-                x_right: tmp_x_max,
-                y_left: tmp_y_min,
-                y_right: tmp_y_max,
-            };
+        Graph2DWidget::axis_ticks(
+            *self.x_scale.borrow(),
+            limit_c.x_left,
+            limit_c.x_right,
+            *self.xn_grid.borrow(),
+        )
+    }
+
+    pub fn y_ticks(&self) -> Vec<f64> {
+        let limit_c = *self.limit_c.borrow();
+
+        Graph2DWidget::axis_ticks(
+            *self.y_scale.borrow(),
+            limit_c.y_left,
+            limit_c.y_right,
+            *self.yn_grid.borrow(),
+        )
+    }
+
+    // Space around the axes box as (left, top, right, bottom): room for the caption and the
+    // axis labels that are set, and for the tick labels next to and below the axes.
+    pub fn margins(
+        label_height: i32,
+        tick_height: i32,
+        tick_width: i32,
+        caption: bool,
+        x_label: bool,
+        y_label: bool,
+    ) -> (i32, i32, i32, i32) {
+        let spc = Graph2DWidget::TEXT_SPC;
+
+        let mut top = 2 * spc;
+        let mut left = spc;
+        let mut bottom = spc;
+        let right = 3 * spc;
+
+        if caption {
+            top += label_height;
+        }
+        if y_label {
+            left += label_height + spc;
+        }
+        if x_label {
+            bottom += label_height + spc;
         }
 
-        self.data.borrow_mut().push(Some(data));
+        left += tick_width + spc;
+        bottom += tick_height + spc;
+
+        (left, top, right, bottom)
     }
 
-    fn handle(&mut self) {
-        let wid = self.widget.clone();
-        let data = self.data.clone();
-        let closest_data_tip = self.closest_data_tip.clone();
-        let data_tips = self.data_tips.clone();
+    // Measures the labels with the fonts they are drawn with. This changes the current font,
+    // so it is done once at the start of each draw and plot_area uses the result.
+    pub fn update_margins(&self) {
+        set_font(Font::Helvetica, Graph2DWidget::LABEL_SIZE);
+        let label_height = height();
+
+        set_font(Font::Helvetica, Graph2DWidget::TICK_SIZE);
+        let tick_height = height();
+        let tick_width = self
+            .y_ticks()
+            .iter()
+            .map(|v| width(&self.format_y(*v)) as i32)
+            .max()
+            .unwrap_or(0);
+
+        *self.margins.borrow_mut() = Graph2DWidget::margins(
+            label_height,
+            tick_height,
+            tick_width,
+            !self.caption.borrow().is_empty(),
+            !self.x_label.borrow().is_empty(),
+            !self.y_label.borrow().is_empty(),
+        );
+    }
 
-        self.widget.widget.handle(move |_, event| {
-            let (mx, my) = fltk::app::event_coords();
-            let widget_width = wid.width();
-            let widget_height = wid.height();
-            let widget_x = wid.x();
-            let widget_y = wid.y();
-            let limit_width = wid.limit_c.borrow().x_right - wid.limit_c.borrow().x_left;
-            let limit_height = wid.limit_c.borrow().y_right - wid.limit_c.borrow().y_left;
+    // Returns the axes box inside the widget, with the margins of the last draw.
+    pub fn plot_area(&self) -> (i32, i32, i32, i32) {
+        let (left, top, right, bottom) = *self.margins.borrow();
 
-            match event {
-                Event::Push => {
-                    let button = fltk::app::event_button();
-                    *closest_data_tip.borrow_mut() = Graph2DWidget::get_closest_datatip(
-                        data_tips.clone(),
-                        mx,
-                        my,
-                        limit_width,
-                        limit_height,
-                        widget_width as f64,
-                        widget_height as f64,
-                        widget_x as f64,
-                        widget_y as f64,
-                        &wid.limit_c.borrow(),
-                    );
+        (
+            self.x() + left,
+            self.y() + top,
+            i32::max(self.width() - left - right, 1),
+            i32::max(self.height() - top - bottom, 1),
+        )
+    }
 
-                    match button {
-                        Graph2DWidget::LEFT_BUTTON => {
-                            // First check if user clicked on a data tip.
+    pub fn scales(&self) -> (AxisScale, AxisScale) {
+        (*self.x_scale.borrow(), *self.y_scale.borrow())
+    }
 
-                            // Find a data tip point close to the mouse pointer.
-                            if closest_data_tip.borrow().is_none() {
-                                // User didn't click on an existing data tip.
-                                let temp_datatip = Graph2DWidget::get_closest_point(
-                                    &*data.borrow(),
-                                    mx,
-                                    my,
-                                    limit_width,
-                                    limit_height,
-                                    widget_width as f64,
-                                    widget_height as f64,
-                                    widget_x as f64,
-                                    widget_y as f64,
-                                    &wid.limit_c.borrow(),
-                                );
-
-                                if let Some(mut temp_datatip) = temp_datatip {
-                                    temp_datatip.lx = 10;
-                                    temp_datatip.ly = -10;
-                                    (*data_tips.borrow_mut()).push(temp_datatip);
-                                } else {
-                                    *wid.zooming.borrow_mut() = true;
-                                    *wid.zoom_x.borrow_mut() = mx;
-                                    *wid.zoom_y.borrow_mut() = my;
-                                }
+    // Same as data_to_pixel, for loops that already know the plot area, limits and scales.
+    pub fn map_to_pixel(
+        area: (i32, i32, i32, i32),
+        limit: &Limit,
+        scales: (AxisScale, AxisScale),
+        x: f64,
+        y: f64,
+    ) -> (f64, f64) {
+        let (plot_x, plot_y, plot_width, plot_height) = area;
+        let (sx, sy) = scales;
+        let (x_left, x_right) = (sx.forward(limit.x_left), sx.forward(limit.x_right));
+        let (y_left, y_right) = (sy.forward(limit.y_left), sy.forward(limit.y_right));
+        let px =
+            ((sx.forward(x) - x_left) / (x_right - x_left)) * plot_width as f64 + plot_x as f64;
+        let py = plot_height as f64
+            - ((sy.forward(y) - y_left) / (y_right - y_left)) * plot_height as f64
+            + plot_y as f64;
+
+        (px, py)
+    }
 
-                                fltk::app::redraw();
-                            }
-                        }
-                        Graph2DWidget::MIDDLE_BUTTON => {
-                            *wid.zoom_x.borrow_mut() = mx;
-                            *wid.zoom_y.borrow_mut() = my;
-                        }
-                        Graph2DWidget::RIGHT_BUTTON => {
-                            if closest_data_tip.borrow().is_some() {
-                                data_tips
-                                    .borrow_mut()
-                                    .remove(closest_data_tip.borrow().unwrap());
-                            } else {
-                                *wid.limit_c.borrow_mut() = *wid.limit.borrow();
-                            }
+    pub fn data_to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        Graph2DWidget::map_to_pixel(
+            self.plot_area(),
+            &self.limit_c.borrow(),
+            self.scales(),
+            x,
+            y,
+        )
+    }
 
-                            fltk::app::redraw();
-                        }
-                        _ => {}
-                    }
+    pub fn pixel_to_data(&self, px: i32, py: i32) -> (f64, f64) {
+        let view = self.view();
+        let (sx, sy) = self.scales();
+        let (plot_x, plot_y, plot_width, plot_height) = self.plot_area();
+        let x =
+            (px - plot_x) as f64 / plot_width as f64 * (view.x_right - view.x_left) + view.x_left;
+        let y = (plot_y + plot_height - py) as f64 / plot_height as f64
+            * (view.y_right - view.y_left)
+            + view.y_left;
+
+        (sx.inverse(x), sy.inverse(y))
+    }
 
-                    true
-                }
-                Event::Drag => {
-                    let button = fltk::app::event_button();
+    // The current view in axis coordinates, where zooming and panning are linear also on
+    // log axes.
+    pub fn view(&self) -> Limit {
+        let limit_c = *self.limit_c.borrow();
+        let (sx, sy) = self.scales();
+
+        Limit {
+            x_left: sx.forward(limit_c.x_left),
+            x_right: sx.forward(limit_c.x_right),
+            y_left: sy.forward(limit_c.y_left),
+            y_right: sy.forward(limit_c.y_right),
+        }
+    }
 
-                    match button {
-                        Graph2DWidget::LEFT_BUTTON => {
-                            if !*wid.zooming.borrow() && closest_data_tip.borrow().is_some() {
-                                let mut mdist =
-                                    widget_width * widget_width + widget_height * widget_height;
+    pub fn set_view(&mut self, view: Limit) {
+        let (sx, sy) = self.scales();
 
-                                let mut data_tip = DataTip::default();
+        *self.limit_c.borrow_mut() = Limit {
+            x_left: sx.inverse(view.x_left),
+            x_right: sx.inverse(view.x_right),
+            y_left: sy.inverse(view.y_left),
+            y_right: sy.inverse(view.y_right),
+        };
+    }
 
-                                if let Some(tip) =
-                                    data_tips.borrow().get(closest_data_tip.borrow().unwrap())
-                                {
-                                    data_tip = *tip;
-                                };
+    // Log axes need positive limits. A lower limit that is not positive moves to the
+    // smallest positive value of the data, and an axis without any positive limit stays
+    // linear. The view history is cleared, as it was recorded with the old scales.
+    pub fn set_scales(&mut self, x: AxisScale, y: AxisScale) {
+        let mut limit = *self.limit.borrow();
+        let mut view = *self.limit_c.borrow();
+        let (x_min, y_min) = self.min_positive();
 
-                                if data_tip.plot_idx < usize::MAX {
-                                    if let Some(dat) = data.borrow().get(data_tip.plot_idx) {
-                                        if let Some(d) = dat {
-                                            for i in 0..d.length {
-                                                if let Some((mut px, mut py)) = d.get_value(i) {
-                                                    px = ((px - wid.limit_c.borrow().x_left)
-                                                        / limit_width)
-                                                        * widget_width as f64
-                                                        + widget_x as f64;
-                                                    py = widget_height as f64
-                                                        - ((py - wid.limit_c.borrow().y_left)
-                                                            / limit_height)
-                                                            * widget_height as f64
-                                                        + widget_y as f64;
-
-                                                    let dist = i32::max(
-                                                        i32::abs(px as i32 - mx),
-                                                        i32::abs(py as i32 - my),
-                                                    );
-                                                    if dist < mdist {
-                                                        if let Some((px, py)) = d.get_value(i) {
-                                                            mdist = dist;
-
-                                                            if let Some(tip_idx) =
-                                                                *closest_data_tip.borrow()
-                                                            {
-                                                                if let Some(tip) = data_tips
-                                                                    .borrow_mut()
-                                                                    .get_mut(tip_idx)
-                                                                {
-                                                                    tip.x = px;
-                                                                    tip.y = py;
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Graph2DWidget::MIDDLE_BUTTON => {
-                            if closest_data_tip.borrow().is_some() {
-                                if let Some(tip_idx) = *closest_data_tip.borrow() {
-                                    if let Some(tip) = data_tips.borrow_mut().get_mut(tip_idx) {
-                                        let mut px = tip.x;
-                                        let mut py = tip.y;
-                                        px = ((px - wid.limit_c.borrow().x_left) / limit_width)
-                                            * widget_width as f64
-                                            + widget_x as f64;
-                                        py = widget_height as f64
-                                            - ((py - wid.limit_c.borrow().y_left) / limit_height)
-                                                * widget_height as f64
-                                            + widget_y as f64;
+        let x = if limit.x_right > 0.0 {
+            x
+        } else {
+            AxisScale::Linear
+        };
+        let y = if limit.y_right > 0.0 {
+            y
+        } else {
+            AxisScale::Linear
+        };
 
-                                        tip.lx = mx - px as i32;
-                                        tip.ly = my - py as i32;
-                                    }
-                                }
-                            } else {
-                                let mut dx = (mx - *wid.zoom_x.borrow()) as f64;
-                                let mut dy = (my - *wid.zoom_y.borrow()) as f64;
+        if x == AxisScale::Log && limit.x_left <= 0.0 {
+            limit.x_left = x_min
+                .filter(|v| *v < limit.x_right)
+                .unwrap_or(limit.x_right / 1000.0);
+        }
+        if x == AxisScale::Log && view.x_left <= 0.0 {
+            view.x_left = limit.x_left;
+            view.x_right = f64::max(view.x_right, limit.x_right);
+        }
+        if y == AxisScale::Log && limit.y_left <= 0.0 {
+            limit.y_left = y_min
+                .filter(|v| *v < limit.y_right)
+                .unwrap_or(limit.y_right / 1000.0);
+        }
+        if y == AxisScale::Log && view.y_left <= 0.0 {
+            view.y_left = limit.y_left;
+            view.y_right = f64::max(view.y_right, limit.y_right);
+        }
 
-                                if f64::abs(dx) >= 1.0 || f64::abs(dy) >= 1.0 {
-                                    dx *= limit_width / widget_width as f64;
-                                    dy *= limit_height / widget_height as f64;
+        *self.limit.borrow_mut() = limit;
+        *self.limit_c.borrow_mut() = view;
+        *self.x_scale.borrow_mut() = x;
+        *self.y_scale.borrow_mut() = y;
 
-                                    *wid.zoom_x.borrow_mut() = mx;
-                                    *wid.zoom_y.borrow_mut() = my;
+        self.clear_history();
+        self.propagate_limits();
+        self.emit(PlotEvent::ViewChanged(view));
+        fltk::app::redraw();
+    }
 
-                                    wid.limit_c.borrow_mut().x_left -= dx;
-                                    wid.limit_c.borrow_mut().x_right -= dx;
-                                    wid.limit_c.borrow_mut().y_left += dy;
-                                    wid.limit_c.borrow_mut().y_right += dy;
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
+    // The smallest positive x and y values of all series.
+    fn min_positive(&self) -> (Option<f64>, Option<f64>) {
+        let data = self.data.borrow();
+        let min = |values: &[f64]| {
+            values
+                .iter()
+                .copied()
+                .filter(|v| *v > 0.0)
+                .min_by(f64::total_cmp)
+        };
+        let x_min = data
+            .iter()
+            .flatten()
+            .filter_map(|d| min(&d.x))
+            .min_by(f64::total_cmp);
+        let y_min = data
+            .iter()
+            .flatten()
+            .filter_map(|d| min(&d.y))
+            .min_by(f64::total_cmp);
+
+        (x_min, y_min)
+    }
 
-                    fltk::app::redraw();
+    pub fn is_inside_plot_area(&self, px: i32, py: i32) -> bool {
+        let (x, y, w, h) = self.plot_area();
 
-                    true
+        px >= x && px < x + w && py >= y && py < y + h
+    }
+
+    pub fn axes_to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        let (plot_x, plot_y, plot_width, plot_height) = self.plot_area();
+        let px = plot_x as f64 + x * plot_width as f64;
+        let py = plot_y as f64 + (1.0 - y) * plot_height as f64;
+
+        (px, py)
+    }
+
+    pub fn to_pixel(&self, x: f64, y: f64, coords: CoordinateSystem) -> (f64, f64) {
+        match coords {
+            CoordinateSystem::Data => self.data_to_pixel(x, y),
+            CoordinateSystem::Axes => self.axes_to_pixel(x, y),
+        }
+    }
+
+    pub fn add_annotation(&mut self, annotation: Annotation) -> usize {
+        self.annotations.borrow_mut().push(annotation);
+        fltk::app::redraw();
+
+        self.annotations.borrow().len() - 1
+    }
+
+    pub fn remove_annotation(&mut self, index: usize) -> Option<Annotation> {
+        if index < self.annotations.borrow().len() {
+            fltk::app::redraw();
+            Some(self.annotations.borrow_mut().remove(index))
+        } else {
+            None
+        }
+    }
+
+    pub fn clear_annotations(&mut self) {
+        self.annotations.borrow_mut().clear();
+        fltk::app::redraw();
+    }
+
+    // Returns the topmost annotation under the mouse pointer. Regions can only be
+    // grabbed at their edges, otherwise panning inside a region would be impossible.
+    pub fn get_closest_annotation(&self, mx: i32, my: i32) -> Option<usize> {
+        let (mx, my) = (mx as f64, my as f64);
+        let near = |a: f64, b: f64| f64::abs(a - b) < 5.0;
+        set_font(Font::Helvetica, Graph2DWidget::LABEL_SIZE);
+
+        for (i, annotation) in self.annotations.borrow().iter().enumerate().rev() {
+            let hit = match annotation {
+                Annotation::Text {
+                    x, y, coords, text, ..
+                } => {
+                    let (px, py) = self.to_pixel(*x, *y, *coords);
+                    let (w, h) = measure(text, false);
+
+                    mx >= px && mx <= px + w as f64 && my >= py - h as f64 && my <= py
                 }
-                Event::Released => {
-                    let button = fltk::app::event_button();
-
-                    match button {
-                        Graph2DWidget::LEFT_BUTTON if *wid.zooming.borrow() => {
-                            let zoom_x = *wid.zoom_x.borrow();
-                            let zoom_y = *wid.zoom_y.borrow();
-
-                            let mut dx = i32::abs(mx - zoom_x);
-                            let mut dy = i32::abs(my - zoom_y);
-                            *wid.zooming.borrow_mut() = false;
-                            *wid.zoom_x.borrow_mut() = i32::min(zoom_x, mx);
-                            *wid.zoom_y.borrow_mut() = i32::min(zoom_y, my);
-
-                            if dx > 2 || dy > 2 {
-                                // Clip
-                                let zoom_x = *wid.zoom_x.borrow();
-                                let zoom_y = *wid.zoom_y.borrow();
-                                *wid.zoom_x.borrow_mut() = i32::max(zoom_x, widget_x);
-                                *wid.zoom_y.borrow_mut() = i32::max(zoom_y, widget_y);
-
-                                let zoom_x = *wid.zoom_x.borrow();
-                                let zoom_y = *wid.zoom_y.borrow();
-                                dx = i32::min(dx, widget_width + widget_x - zoom_x);
-                                dy = i32::min(dy, widget_height + widget_y - zoom_y);
-
-                                // Scale + shift
-                                let x_left = wid.limit_c.borrow().x_left;
-                                let y_left = wid.limit_c.borrow().y_left;
-
-                                wid.limit_c.borrow_mut().x_left = ((zoom_x - widget_x) as f64
-                                    / widget_width as f64)
-                                    * limit_width
-                                    + x_left;
-                                let x_left = wid.limit_c.borrow().x_left;
-                                wid.limit_c.borrow_mut().x_right =
-                                    x_left + (dx as f64 / widget_width as f64) * limit_width;
-                                wid.limit_c.borrow_mut().y_left =
-                                    ((widget_y + widget_height - dy - zoom_y) as f64
-                                        / widget_height as f64)
-                                        * limit_height
-                                        + y_left;
-                                let y_left = wid.limit_c.borrow().y_left;
-                                wid.limit_c.borrow_mut().y_right =
-                                    y_left + (dy as f64 / widget_height as f64) * limit_height;
-                            }
+                Annotation::Arrow {
+                    x0,
+                    y0,
+                    x1,
+                    y1,
+                    coords,
+                    ..
+                } => {
+                    let (px0, py0) = self.to_pixel(*x0, *y0, *coords);
+                    let (px1, py1) = self.to_pixel(*x1, *y1, *coords);
+
+                    (near(px0, mx) && near(py0, my)) || (near(px1, mx) && near(py1, my))
+                }
+                Annotation::XLine { x, .. } => near(self.data_to_pixel(*x, 0.0).0, mx),
+                Annotation::YLine { y, .. } => near(self.data_to_pixel(0.0, *y).1, my),
+                Annotation::XRegion {
+                    x_left, x_right, ..
+                } => {
+                    near(self.data_to_pixel(*x_left, 0.0).0, mx)
+                        || near(self.data_to_pixel(*x_right, 0.0).0, mx)
+                }
+                Annotation::YRegion {
+                    y_left, y_right, ..
+                } => {
+                    near(self.data_to_pixel(0.0, *y_left).1, my)
+                        || near(self.data_to_pixel(0.0, *y_right).1, my)
+                }
+            };
 
-                            fltk::app::redraw();
-                        }
-                        _ => {}
+            if hit {
+                return Some(i);
+            }
+        }
+
+        None
+    }
+
+    // Moves an annotation by the mouse drag from one pixel position to another. The data
+    // delta goes through the axis scales, the axes delta is a fraction of the plot area.
+    pub fn move_annotation(&mut self, index: usize, from: (i32, i32), to: (i32, i32)) {
+        let (_, _, width, height) = self.plot_area();
+        let (x0, y0) = self.pixel_to_data(from.0, from.1);
+        let (x1, y1) = self.pixel_to_data(to.0, to.1);
+        let (x_scale, y_scale) = self.scales();
+
+        if let Some(annotation) = self.annotations.borrow_mut().get_mut(index) {
+            annotation.translate(
+                x_scale.forward(x1) - x_scale.forward(x0),
+                y_scale.forward(y1) - y_scale.forward(y0),
+                (to.0 - from.0) as f64 / width as f64,
+                (from.1 - to.1) as f64 / height as f64,
+                (x_scale, y_scale),
+            );
+        }
+    }
+
+    // Shaded spans are drawn below the grid and the data.
+    pub fn draw_regions(&self) {
+        let (x, y, w, h) = self.plot_area();
+
+        for annotation in self.annotations.borrow().iter() {
+            match annotation {
+                Annotation::XRegion {
+                    x_left,
+                    x_right,
+                    color,
+                } => {
+                    let left = self.data_to_pixel(f64::min(*x_left, *x_right), 0.0).0 as i32;
+                    let right = self.data_to_pixel(f64::max(*x_left, *x_right), 0.0).0 as i32;
+                    let left = i32::max(left, x);
+                    let right = i32::min(right, x + w);
+
+                    if right > left {
+                        draw_rect_fill(
+                            left,
+                            y,
+                            right - left,
+                            h,
+                            Color::color_average(*color, Color::White, 0.25),
+                        );
+                    }
+                }
+                Annotation::YRegion {
+                    y_left,
+                    y_right,
+                    color,
+                } => {
+                    let top = self.data_to_pixel(0.0, f64::max(*y_left, *y_right)).1 as i32;
+                    let bottom = self.data_to_pixel(0.0, f64::min(*y_left, *y_right)).1 as i32;
+                    let top = i32::max(top, y);
+                    let bottom = i32::min(bottom, y + h);
+
+                    if bottom > top {
+                        draw_rect_fill(
+                            x,
+                            top,
+                            w,
+                            bottom - top,
+                            Color::color_average(*color, Color::White, 0.25),
+                        );
                     }
+                }
+                _ => {}
+            }
+        }
+    }
 
-                    true
+    // Text, arrows and reference lines are drawn on top of the data.
+    pub fn draw_annotations(&self) {
+        let (x, y, w, h) = self.plot_area();
+        set_font(Font::Helvetica, Graph2DWidget::LABEL_SIZE);
+
+        for annotation in self.annotations.borrow().iter() {
+            match annotation {
+                Annotation::Text {
+                    x: tx,
+                    y: ty,
+                    coords,
+                    text,
+                    color,
+                } => {
+                    let (px, py) = self.to_pixel(*tx, *ty, *coords);
+
+                    set_draw_color(*color);
+                    draw_text(text, px as i32, py as i32);
                 }
-                Event::MouseWheel => {
-                    let coefficient = match fltk::app::event_dy() {
-                        MouseWheel::None => 0.0,
-                        MouseWheel::Down => 0.1,
-                        MouseWheel::Up => -0.1,
-                        MouseWheel::Right => 0.0,
-                        MouseWheel::Left => 0.0,
-                    };
+                Annotation::Arrow {
+                    x0,
+                    y0,
+                    x1,
+                    y1,
+                    coords,
+                    width,
+                    color,
+                } => {
+                    let (px0, py0) = self.to_pixel(*x0, *y0, *coords);
+                    let (px1, py1) = self.to_pixel(*x1, *y1, *coords);
+                    let angle = f64::atan2(py1 - py0, px1 - px0);
+                    let head = 10.0 + 2.0 * *width as f64;
+                    let spread = 25.0_f64.to_radians();
+
+                    set_draw_color(*color);
+                    set_line_style(LineStyle::Solid, *width);
+                    draw_line(px0 as i32, py0 as i32, px1 as i32, py1 as i32);
+                    set_line_style(LineStyle::Solid, 1);
+                    draw_polygon(
+                        px1 as i32,
+                        py1 as i32,
+                        (px1 - head * f64::cos(angle - spread)) as i32,
+                        (py1 - head * f64::sin(angle - spread)) as i32,
+                        (px1 - head * f64::cos(angle + spread)) as i32,
+                        (py1 - head * f64::sin(angle + spread)) as i32,
+                    );
+                }
+                Annotation::XLine {
+                    x: lx,
+                    label,
+                    style,
+                    width,
+                    color,
+                } => {
+                    let px = self.data_to_pixel(*lx, 0.0).0 as i32;
+
+                    set_draw_color(*color);
+                    set_line_style(*style, *width);
+                    draw_line(px, y, px, y + h);
+                    draw_text(label, px + 3, y + 14);
+                }
+                Annotation::YLine {
+                    y: ly,
+                    label,
+                    style,
+                    width: line_width,
+                    color,
+                } => {
+                    let py = self.data_to_pixel(0.0, *ly).1 as i32;
+
+                    set_draw_color(*color);
+                    set_line_style(*style, *line_width);
+                    draw_line(x, py, x + w, py);
+                    draw_text(label, x + w - width(label) as i32 - 3, py - 3);
+                }
+                _ => {}
+            }
+        }
 
-                    // mx, my are in window space.
-                    // widget_x, widget_y are in plot widget coordinates.
-                    // widget_width, widget_height are the absolute widget sizes in pixels.
-                    let mxx = (mx as f64 - widget_x as f64) / widget_width as f64 * limit_width;
-                    let myy = (widget_height as f64 + widget_y as f64 - my as f64)
-                        / widget_height as f64
-                        * limit_height;
-                    let dxr = limit_width - mxx as f64;
-                    let dyr = limit_height - myy as f64;
+        set_line_style(LineStyle::Solid, 1);
+    }
 
-                    let tmp_limit = *wid.limit_c.borrow();
+    // Converts an area in axes coordinates, given as (x, y, width, height) with (x, y) the
+    // bottom left corner, to the pixel rectangle an inset widget should occupy.
+    pub fn inset_area(&self, area: (f64, f64, f64, f64)) -> (i32, i32, i32, i32) {
+        let (_, _, plot_width, plot_height) = self.plot_area();
+        let (x, y) = self.axes_to_pixel(area.0, area.1 + area.3);
+
+        (
+            x as i32,
+            y as i32,
+            (area.2 * plot_width as f64) as i32,
+            (area.3 * plot_height as f64) as i32,
+        )
+    }
 
-                    wid.limit_c.borrow_mut().x_left = (mxx as f64 * coefficient) + tmp_limit.x_left;
-                    wid.limit_c.borrow_mut().x_right = tmp_limit.x_right - (dxr * coefficient);
-                    wid.limit_c.borrow_mut().y_left = (myy as f64 * coefficient) + tmp_limit.y_left;
-                    wid.limit_c.borrow_mut().y_right = tmp_limit.y_right - (dyr * coefficient);
+    // Makes the inset show a region of this graph. The inset gets a copy of the series if it
+    // has no data of its own, and the region is marked with a rectangle that follows the
+    // view of the inset when it is zoomed or panned.
+    pub fn link_inset(&mut self, inset: &mut Graph2DWidget, region: Limit) {
+        if inset.data.borrow().is_empty() {
+            *inset.data.borrow_mut() = self.data.borrow().clone();
+        }
 
-                    fltk::app::redraw();
+        *inset.limit.borrow_mut() = region;
+        *inset.limit_c.borrow_mut() = region;
 
-                    true
-                }
-                _ => false,
+        self.inset_marks.borrow_mut().push(inset.limit_c.clone());
+        fltk::app::redraw();
+    }
+
+    pub fn draw_inset_marks(&self) {
+        let area = self.plot_area();
+        let limit_c = *self.limit_c.borrow();
+        let scales = self.scales();
+
+        set_line_style(LineStyle::Dash, 1);
+        set_draw_color(Color::Dark3);
+
+        for mark in self.inset_marks.borrow().iter() {
+            let mark = *mark.borrow();
+            let (x0, y0) =
+                Graph2DWidget::map_to_pixel(area, &limit_c, scales, mark.x_left, mark.y_right);
+            let (x1, y1) =
+                Graph2DWidget::map_to_pixel(area, &limit_c, scales, mark.x_right, mark.y_left);
+
+            draw_rect(x0 as i32, y0 as i32, (x1 - x0) as i32, (y1 - y0) as i32);
+        }
+
+        set_line_style(LineStyle::Solid, 1);
+    }
+
+    // Joins the group and takes over the view of the graphs that are already in it.
+    pub fn link_axes(&mut self, group: &AxisLinkGroup) {
+        group.add(&self.limit_c);
+        group.adopt(&self.limit_c);
+        self.axis_links.borrow_mut().push(group.clone());
+        fltk::app::redraw();
+    }
+
+    // Leaves the link groups of the graph, or joins them again and takes over their view. The
+    // graph is only linked while its window is shown, so hidden windows are not updated.
+    pub fn set_linked(&mut self, on: bool) {
+        let groups = self.axis_links.borrow().clone();
+
+        for group in groups {
+            if !on {
+                group.remove(&self.limit_c);
+            } else if group.add(&self.limit_c) {
+                group.adopt(&self.limit_c);
             }
-        });
+        }
+        fltk::app::redraw();
     }
 
-    fn set_grid(&mut self, on: bool) {
-        self.widget.set_grid(on);
+    // Pushes the current view to all graphs linked to this one.
+    pub fn propagate_limits(&self) {
+        for group in self.axis_links.borrow().iter() {
+            group.propagate(&self.limit_c);
+        }
     }
 
-    fn set_x_label(&mut self, label: &str) {
-        self.widget.set_x_label(label);
+    pub fn set_sync_cursor(&mut self, cursor: Option<SyncCursor>) {
+        *self.sync_cursor.borrow_mut() = cursor;
+        fltk::app::redraw();
     }
 
-    fn set_y_label(&mut self, label: &str) {
-        self.widget.set_y_label(label);
+    // Vertical line at the shared cursor position, with the interpolated value of each
+    // series where it crosses the line.
+    pub fn draw_sync_cursor(&self) {
+        let cx = match self.sync_cursor.borrow().as_ref() {
+            Some(cursor) => *cursor.x.borrow(),
+            None => None,
+        };
+
+        if let Some(cx) = cx {
+            let area = self.plot_area();
+            let (x, y, w, h) = area;
+            let limit_c = *self.limit_c.borrow();
+            let scales = self.scales();
+            let px = Graph2DWidget::map_to_pixel(area, &limit_c, scales, cx, 0.0).0 as i32;
+
+            if px < x || px > x + w {
+                return;
+            }
+
+            set_font(Font::Helvetica, Graph2DWidget::TICK_SIZE);
+            set_line_style(LineStyle::Solid, 1);
+            set_draw_color(Color::Dark2);
+            draw_line(px, y, px, y + h);
+            draw_text(&format!("x: {}", self.format_x(cx)), px + 4, y + height());
+
+            for d in self.data.borrow().iter().flatten().filter(|d| d.visible) {
+                if let Some(cy) = d.interpolate(cx) {
+                    let py = Graph2DWidget::map_to_pixel(area, &limit_c, scales, cx, cy).1 as i32;
+
+                    set_draw_color(d.color);
+                    draw_circle(px as f64, py as f64, 3.0);
+                    draw_text(&self.format_y(cy), px + 6, py - 4);
+                }
+            }
+        }
     }
 
-    fn set_caption(&mut self, caption: &str) {
-        self.widget.set_caption(caption);
+    pub fn set_mode(&mut self, mode: InteractionMode) {
+        *self.mode.borrow_mut() = mode;
+        *self.zooming.borrow_mut() = false;
+
+        if mode == InteractionMode::Measure && self.measure_cursors.borrow().is_none() {
+            self.show_measure_cursors(false);
+        }
+
+        fltk::app::redraw();
     }
 
-    fn set_hold(&mut self, hold: bool) {
-        self.widget.set_hold(hold);
+    pub fn reset_view(&mut self) {
+        let before = *self.limit_c.borrow();
+
+        *self.limit_c.borrow_mut() = *self.limit.borrow();
+        self.propagate_limits();
+        self.record_view(before);
+        fltk::app::redraw();
     }
-}
 
-impl Deref for Graph2DWidget {
-    type Target = GraphWidget;
+    // Adds the current view to the history after a zoom or pan that started from the view
+    // before. Views after the current position in the history are dropped.
+    pub fn record_view(&mut self, before: Limit) {
+        // A wheel gesture that is still going on ends where this change starts.
+        self.end_wheel(before);
 
-    fn deref(&self) -> &Self::Target {
-        &self.widget
+        let current = *self.limit_c.borrow();
+        if self.push_history(before, current) {
+            self.emit(PlotEvent::ViewChanged(current));
+        }
     }
-}
 
-impl DerefMut for Graph2DWidget {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.widget
+    fn push_history(&mut self, before: Limit, current: Limit) -> bool {
+        if current == before {
+            return false;
+        }
+
+        let mut history = self.history.borrow_mut();
+        let mut pos = self.history_pos.borrow_mut();
+
+        if history.is_empty() {
+            history.push(before);
+        } else {
+            history.truncate(*pos + 1);
+        }
+
+        if history.last() != Some(&current) {
+            history.push(current);
+        }
+
+        *pos = history.len() - 1;
+        true
+    }
+
+    // Every wheel step is reported as a view change, but consecutive steps are one entry in
+    // the history: it is added once the wheel has been idle for a moment, or when another
+    // change of the view comes first. Each step pushes the one timer back.
+    pub fn wheel_step(&mut self, before: Limit) {
+        if self.wheel_start.borrow().is_none() {
+            *self.wheel_start.borrow_mut() = Some(before);
+        }
+
+        let current = *self.limit_c.borrow();
+        if current != before {
+            self.emit(PlotEvent::ViewChanged(current));
+        }
+
+        let timer = self.wheel_timer.borrow_mut().take();
+        if let Some(handle) = timer {
+            fltk::app::remove_timeout3(handle);
+        }
+
+        let mut graph = self.clone();
+        let handle = fltk::app::add_timeout3(Graph2DWidget::WHEEL_IDLE, move |_| {
+            *graph.wheel_timer.borrow_mut() = None;
+            graph.finish_wheel();
+        });
+        *self.wheel_timer.borrow_mut() = Some(handle);
+    }
+
+    // Zooms or pans for a wheel or trackpad scroll of (dx, dy) steps at the mouse pointer.
+    // Right and down are positive, trackpads report more than one step for fast movements.
+    pub fn handle_wheel(&mut self, mx: i32, my: i32, dx: f64, dy: f64, shift: bool, ctrl: bool) {
+        let wheel = *self.wheel.borrow();
+        let before = *self.limit_c.borrow();
+
+        match wheel.action {
+            WheelAction::Zoom => {
+                // Some platforms turn Shift+wheel into a horizontal scroll.
+                let (steps, pan) = if dy == 0.0 && shift {
+                    (dx, 0.0)
+                } else {
+                    (dy, dx)
+                };
+
+                // Shift zooms only x, Ctrl zooms only y.
+                let axis = if shift {
+                    ZoomAxis::X
+                } else if ctrl {
+                    ZoomAxis::Y
+                } else {
+                    ZoomAxis::Both
+                };
+
+                let coefficient = (steps * wheel.zoom_step).clamp(-0.5, 0.5);
+                self.zoom_at(mx, my, coefficient, axis);
+                self.pan_view(pan * wheel.pan_step, 0.0);
+            }
+            WheelAction::Pan if ctrl => {
+                let coefficient = (dy * wheel.zoom_step).clamp(-0.5, 0.5);
+                self.zoom_at(mx, my, coefficient, ZoomAxis::Both);
+            }
+            WheelAction::Pan => {
+                self.pan_view(dx * wheel.pan_step, -dy * wheel.pan_step);
+            }
+        }
+
+        self.wheel_step(before);
+        fltk::app::redraw();
+    }
+
+    // Whether a click was made with the button bound to the tool, which reports the point
+    // under the mouse pointer. That is not necessarily the left button.
+    pub fn is_tool_click(drag_action: MouseAction, click_action: MouseAction) -> bool {
+        (drag_action == MouseAction::Tool || click_action == MouseAction::Tool)
+            && click_action != MouseAction::ContextMenu
+    }
+
+    pub fn click_point(&mut self, mx: i32, my: i32) {
+        let sample = self.get_closest_sample(mx, my);
+        let value = sample.and_then(|(series, index)| {
+            let (x, y) = self.data.borrow().get(series)?.as_ref()?.get_value(index)?;
+            Some((series, index, x, y))
+        });
+
+        if let Some((series, index, x, y)) = value {
+            self.emit(PlotEvent::PointClicked {
+                series,
+                index,
+                x,
+                y,
+            });
+        }
+    }
+
+    // Ends a wheel gesture at the current view, its changes were reported already.
+    pub fn finish_wheel(&mut self) {
+        let current = *self.limit_c.borrow();
+        self.end_wheel(current);
+    }
+
+    fn end_wheel(&mut self, end: Limit) {
+        let timer = self.wheel_timer.borrow_mut().take();
+        if let Some(handle) = timer {
+            fltk::app::remove_timeout3(handle);
+        }
+
+        let wheel_start = self.wheel_start.borrow_mut().take();
+        if let Some(start) = wheel_start {
+            self.push_history(start, end);
+        }
+    }
+
+    pub fn set_event_callback(&mut self, callback: Option<EventCallback>) {
+        *self.event_callback.borrow_mut() = callback;
+    }
+
+    pub fn emit_mouse_move(&self, mx: i32, my: i32) {
+        if self.is_inside_plot_area(mx, my) {
+            let (x, y) = self.pixel_to_data(mx, my);
+            self.emit(PlotEvent::MouseMoved { x, y });
+        }
+    }
+
+    // The callback may change the graph, so no state is borrowed while it runs.
+    pub fn emit(&self, event: PlotEvent) {
+        let callback = self.event_callback.borrow().clone();
+
+        if let Some(callback) = callback {
+            callback(&event);
+        }
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.borrow_mut().clear();
+        *self.history_pos.borrow_mut() = 0;
+    }
+
+    // Steps through the view history, a negative step goes back. Returns false if there
+    // is no view in that direction.
+    pub fn step_history(&mut self, step: isize) -> bool {
+        self.finish_wheel();
+
+        let view = {
+            let history = self.history.borrow();
+            let mut pos = self.history_pos.borrow_mut();
+
+            match pos.checked_add_signed(step).filter(|p| *p < history.len()) {
+                Some(p) => {
+                    *pos = p;
+                    history[p]
+                }
+                None => return false,
+            }
+        };
+
+        *self.limit_c.borrow_mut() = view;
+        self.propagate_limits();
+        self.emit(PlotEvent::ViewChanged(view));
+        fltk::app::redraw();
+
+        true
+    }
+
+    pub fn view_back(&mut self) -> bool {
+        self.step_history(-1)
+    }
+
+    pub fn view_forward(&mut self) -> bool {
+        self.step_history(1)
+    }
+
+    // Zooms around the data point under the mouse pointer. A positive coefficient zooms
+    // in, 0.1 shrinks the view by 10%.
+    pub fn zoom_at(&mut self, mx: i32, my: i32, coefficient: f64, axis: ZoomAxis) {
+        let (plot_x, plot_y, plot_width, plot_height) = self.plot_area();
+        let mut limit_c = self.view();
+        let limit_width = limit_c.x_right - limit_c.x_left;
+        let limit_height = limit_c.y_right - limit_c.y_left;
+
+        // Distances from the left and bottom edge of the view to the mouse pointer.
+        let mxx = (mx - plot_x) as f64 / plot_width as f64 * limit_width;
+        let myy = (plot_y + plot_height - my) as f64 / plot_height as f64 * limit_height;
+
+        if axis != ZoomAxis::Y {
+            limit_c.x_left += mxx * coefficient;
+            limit_c.x_right -= (limit_width - mxx) * coefficient;
+        }
+        if axis != ZoomAxis::X {
+            limit_c.y_left += myy * coefficient;
+            limit_c.y_right -= (limit_height - myy) * coefficient;
+        }
+        self.set_view(limit_c);
+
+        self.propagate_limits();
+    }
+
+    // Moves the view by fractions of its width and height.
+    pub fn pan_view(&mut self, fx: f64, fy: f64) {
+        let mut limit_c = self.view();
+        let dx = fx * (limit_c.x_right - limit_c.x_left);
+        let dy = fy * (limit_c.y_right - limit_c.y_left);
+
+        limit_c.x_left += dx;
+        limit_c.x_right += dx;
+        limit_c.y_left += dy;
+        limit_c.y_right += dy;
+        self.set_view(limit_c);
+
+        self.propagate_limits();
+    }
+
+    // Returns the axis whose tick labels are under the mouse pointer.
+    pub fn axis_region(&self, mx: i32, my: i32) -> Option<ZoomAxis> {
+        let (x, y, w, h) = self.plot_area();
+
+        if mx >= x && mx < x + w && my >= y + h && my < self.y() + self.height() {
+            Some(ZoomAxis::X)
+        } else if my >= y && my < y + h && mx >= self.x() && mx < x {
+            Some(ZoomAxis::Y)
+        } else {
+            None
+        }
+    }
+
+    // A box zoom changes only x with Shift held or in the ZoomX mode, and only y with Ctrl
+    // held or in the ZoomY mode.
+    pub fn start_zoom(&mut self, mx: i32, my: i32) {
+        *self.zoom_axis.borrow_mut() = match *self.mode.borrow() {
+            InteractionMode::ZoomX => ZoomAxis::X,
+            InteractionMode::ZoomY => ZoomAxis::Y,
+            _ if fltk::app::is_event_shift() => ZoomAxis::X,
+            _ if fltk::app::is_event_ctrl() => ZoomAxis::Y,
+            _ => ZoomAxis::Both,
+        };
+
+        *self.zooming.borrow_mut() = true;
+        *self.zoom_x.borrow_mut() = mx;
+        *self.zoom_y.borrow_mut() = my;
+    }
+
+    pub fn start_pan(&mut self, mx: i32, my: i32, axis: ZoomAxis) {
+        *self.pan_axis.borrow_mut() = axis;
+        *self.zoom_x.borrow_mut() = mx;
+        *self.zoom_y.borrow_mut() = my;
+    }
+
+    // Moves the view along with the mouse, starting from the position in zoom_x, zoom_y.
+    pub fn pan_to(&mut self, mx: i32, my: i32) {
+        let (_, _, plot_width, plot_height) = self.plot_area();
+        let mut dx = (mx - *self.zoom_x.borrow()) as f64;
+        let mut dy = (my - *self.zoom_y.borrow()) as f64;
+
+        match *self.pan_axis.borrow() {
+            ZoomAxis::X => dy = 0.0,
+            ZoomAxis::Y => dx = 0.0,
+            ZoomAxis::Both => {}
+        }
+
+        if f64::abs(dx) >= 1.0 || f64::abs(dy) >= 1.0 {
+            let mut limit_c = self.view();
+            dx *= (limit_c.x_right - limit_c.x_left) / plot_width as f64;
+            dy *= (limit_c.y_right - limit_c.y_left) / plot_height as f64;
+
+            *self.zoom_x.borrow_mut() = mx;
+            *self.zoom_y.borrow_mut() = my;
+
+            limit_c.x_left -= dx;
+            limit_c.x_right -= dx;
+            limit_c.y_left += dy;
+            limit_c.y_right += dy;
+            self.set_view(limit_c);
+
+            self.propagate_limits();
+        }
+    }
+
+    // Returns the series and sample index of the point closest to the mouse pointer, if
+    // it is within 10 pixels.
+    pub fn get_closest_sample(&self, mx: i32, my: i32) -> Option<(usize, usize)> {
+        self.nearest_sample(None, mx, my, 10.0)
+    }
+
+    // Same as get_closest_sample, for one series only or all of them, and any distance.
+    pub fn nearest_sample(
+        &self,
+        series: Option<usize>,
+        mx: i32,
+        my: i32,
+        max_dist: f64,
+    ) -> Option<(usize, usize)> {
+        let area = self.plot_area();
+        let limit_c = *self.limit_c.borrow();
+        let scales = self.scales();
+        let mut closest = None;
+        let mut m_dist = max_dist;
+
+        for (j, d) in self.data.borrow().iter().enumerate() {
+            if series.is_some_and(|s| s != j) {
+                continue;
+            }
+
+            if let Some(d) = d.as_ref().filter(|d| d.visible) {
+                for i in 0..d.length {
+                    let (px, py) =
+                        Graph2DWidget::map_to_pixel(area, &limit_c, scales, d.x[i], d.y[i]);
+                    let dist = f64::max(f64::abs(px - mx as f64), f64::abs(py - my as f64));
+
+                    if dist <= m_dist {
+                        m_dist = dist;
+                        closest = Some((j, i));
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    // Returns the series and the position on it where a data tip goes for the mouse
+    // pointer, following the tip snap mode.
+    pub fn tip_position(
+        &self,
+        series: Option<usize>,
+        mx: i32,
+        my: i32,
+        max_dist: f64,
+    ) -> Option<(usize, f64, f64)> {
+        if *self.tip_snap.borrow() == TipSnap::Nearest {
+            let (series, index) = self.nearest_sample(series, mx, my, max_dist)?;
+            let (x, y) = self.data.borrow()[series].as_ref()?.get_value(index)?;
+
+            return Some((series, x, y));
+        }
+
+        let area = self.plot_area();
+        let limit_c = *self.limit_c.borrow();
+        let (x_scale, y_scale) = self.scales();
+        let (mx, my) = (mx as f64, my as f64);
+        let mut closest = None;
+        let mut m_dist = max_dist;
+
+        for (j, d) in self.data.borrow().iter().enumerate() {
+            if series.is_some_and(|s| s != j) {
+                continue;
+            }
+
+            let Some(d) = d.as_ref().filter(|d| d.visible && d.length > 0) else {
+                continue;
+            };
+
+            // A series with a single sample is a segment of length zero.
+            for i in 0..usize::max(d.length - 1, 1) {
+                let k = usize::min(i + 1, d.length - 1);
+                let (x0, y0, x1, y1) = (d.x[i], d.y[i], d.x[k], d.y[k]);
+                let to_pixel =
+                    |x, y| Graph2DWidget::map_to_pixel(area, &limit_c, (x_scale, y_scale), x, y);
+                let (px0, py0) = to_pixel(x0, y0);
+                let (px1, py1) = to_pixel(x1, y1);
+
+                if !(px0.is_finite() && py0.is_finite() && px1.is_finite() && py1.is_finite()) {
+                    continue;
+                }
+
+                let (dx, dy) = (px1 - px0, py1 - py0);
+                let len_sq = dx * dx + dy * dy;
+                let t = if len_sq > 0.0 {
+                    (((mx - px0) * dx + (my - py0) * dy) / len_sq).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let dist = f64::hypot(px0 + t * dx - mx, py0 + t * dy - my);
+
+                if dist <= m_dist {
+                    // The line is straight on the axis scales, not necessarily in data space.
+                    // The ends are kept exact, so that the tip is on the sample.
+                    let lerp = |scale: AxisScale, a: f64, b: f64| match t {
+                        0.0 => a,
+                        1.0 => b,
+                        _ => scale
+                            .inverse(scale.forward(a) + t * (scale.forward(b) - scale.forward(a))),
+                    };
+
+                    m_dist = dist;
+                    closest = Some((j, lerp(x_scale, x0, x1), lerp(y_scale, y0, y1)));
+                }
+            }
+        }
+
+        closest
+    }
+
+    // Applies the snap steps of the edit configuration to a data position.
+    pub fn snap(&self, x: f64, y: f64) -> (f64, f64) {
+        let snap = |v: f64, step: f64| {
+            if step > 0.0 {
+                (v / step).round() * step
+            } else {
+                v
+            }
+        };
+
+        match self.edit.borrow().snap {
+            Some((x_step, y_step)) => (snap(x, x_step), snap(y, y_step)),
+            None => (x, y),
+        }
+    }
+
+    // Moves a sample to the mouse pointer, within the constraints of the edit configuration.
+    pub fn move_point(&mut self, series: usize, index: usize, mx: i32, my: i32) {
+        let (x, y) = self.pixel_to_data(mx, my);
+        let (mut x, y) = self.snap(x, y);
+        let edit = *self.edit.borrow();
+
+        if let Some(Some(d)) = self.data.borrow_mut().get_mut(series) {
+            if index >= d.length {
+                return;
+            }
+
+            if edit.lock_x {
+                x = d.x[index];
+            }
+            if edit.monotonic_x && index > 0 {
+                x = f64::max(x, d.x[index - 1]);
+            }
+            if edit.monotonic_x && index + 1 < d.length {
+                x = f64::min(x, d.x[index + 1]);
+            }
+
+            d.set_value(index, x, y);
+        }
+    }
+
+    // Inserts a point where its x keeps the series sorted, or at the end if the series is not
+    // sorted by x. Returns the sample index of the new point.
+    pub fn insert_point(&mut self, series: usize, x: f64, y: f64) -> Option<usize> {
+        let index = {
+            let mut data = self.data.borrow_mut();
+            let d = data.get_mut(series)?.as_mut()?;
+            let index = if d.x.windows(2).all(|w| w[0] <= w[1]) {
+                d.x.partition_point(|v| *v <= x)
+            } else {
+                d.length
+            };
+
+            d.insert_value(index, x, y);
+            index
+        };
+
+        self.data_changed(series);
+        fltk::app::redraw();
+        Some(index)
+    }
+
+    // Inserts a point at the mouse pointer into the closest visible series.
+    pub fn insert_point_at(&mut self, mx: i32, my: i32) -> Option<usize> {
+        let (series, _) = self.nearest_sample(None, mx, my, f64::INFINITY)?;
+        let (x, y) = self.pixel_to_data(mx, my);
+        let (x, y) = self.snap(x, y);
+
+        self.insert_point(series, x, y)
+    }
+
+    // Removes a point, along with the data tips on it.
+    pub fn delete_point(&mut self, series: usize, index: usize) -> Option<(f64, f64)> {
+        let (x, y) = self
+            .data
+            .borrow_mut()
+            .get_mut(series)?
+            .as_mut()?
+            .remove_value(index)?;
+
+        let tips: Vec<usize> = self
+            .data_tips
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, tip)| tip.plot_idx == series && tip.x == x && tip.y == y)
+            .map(|(i, _)| i)
+            .collect();
+        for i in tips.into_iter().rev() {
+            self.remove_data_tip(i);
+        }
+
+        self.data_changed(series);
+        fltk::app::redraw();
+        Some((x, y))
+    }
+
+    pub fn set_data_callback(&mut self, callback: Option<DataCallback>) {
+        *self.data_callback.borrow_mut() = callback;
+    }
+
+    // Passes a copy of the series to the callback, so it is free to change the graph.
+    pub fn data_changed(&self, series: usize) {
+        let callback = self.data_callback.borrow().clone();
+        let data = self.data.borrow().get(series).cloned().flatten();
+
+        if let (Some(callback), Some(data)) = (callback, data) {
+            callback(series, &data);
+        }
+    }
+
+    pub fn set_crosshair(&mut self, status: Option<StatusLine>) {
+        *self.crosshair.borrow_mut() = status;
+        *self.pointer.borrow_mut() = None;
+        fltk::app::redraw();
+    }
+
+    // Follows the mouse pointer with the crosshair and writes its data coordinates to the
+    // status line. Outside the plot area the crosshair is hidden.
+    pub fn track_pointer(&mut self, pointer: Option<(i32, i32)>) {
+        let status = match self.crosshair.borrow().as_ref() {
+            Some(status) => status.clone(),
+            None => return,
+        };
+
+        let pointer = pointer.filter(|(mx, my)| self.is_inside_plot_area(*mx, *my));
+        *self.pointer.borrow_mut() = pointer;
+
+        *status.text.borrow_mut() = match pointer {
+            Some((mx, my)) => {
+                let (x, y) = self.pixel_to_data(mx, my);
+                format!("x: {}   y: {}", self.format_x(x), self.format_y(y))
+            }
+            None => String::new(),
+        };
+
+        set_cursor(if pointer.is_some() {
+            Cursor::Cross
+        } else {
+            Cursor::Default
+        });
+        fltk::app::redraw();
+    }
+
+    pub fn draw_crosshair(&self) {
+        if let Some((mx, my)) = *self.pointer.borrow() {
+            let (x, y, w, h) = self.plot_area();
+
+            set_line_style(LineStyle::Solid, 1);
+            set_draw_color(Color::Dark3);
+            draw_line(mx, y, mx, y + h);
+            draw_line(x, my, x + w, my);
+        }
+    }
+
+    pub fn set_measure_cursors(&mut self, cursors: Option<MeasureCursors>) {
+        *self.measure_cursors.borrow_mut() = cursors;
+        fltk::app::redraw();
+    }
+
+    // Places the measurement cursors at one and two thirds of the current view.
+    pub fn show_measure_cursors(&mut self, horizontal: bool) {
+        let limit_c = *self.limit_c.borrow();
+        let dx = (limit_c.x_right - limit_c.x_left) / 3.0;
+        let dy = (limit_c.y_right - limit_c.y_left) / 3.0;
+
+        self.set_measure_cursors(Some(MeasureCursors {
+            x1: limit_c.x_left + dx,
+            x2: limit_c.x_right - dx,
+            y1: limit_c.y_left + dy,
+            y2: limit_c.y_right - dy,
+            horizontal,
+        }));
+    }
+
+    // Returns the index of each visible series with its values at the x1 and x2 cursors.
+    pub fn measure_values(&self) -> Vec<(usize, Option<f64>, Option<f64>)> {
+        match *self.measure_cursors.borrow() {
+            Some(c) => self
+                .data
+                .borrow()
+                .iter()
+                .enumerate()
+                .filter_map(|(i, d)| d.as_ref().filter(|d| d.visible).map(|d| (i, d)))
+                .map(|(i, d)| (i, d.interpolate(c.x1), d.interpolate(c.x2)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_closest_cursor_line(&self, mx: i32, my: i32) -> Option<CursorLine> {
+        let c = (*self.measure_cursors.borrow())?;
+        let (px1, py1) = self.data_to_pixel(c.x1, c.y1);
+        let (px2, py2) = self.data_to_pixel(c.x2, c.y2);
+        let near = |a: f64, b: i32| f64::abs(a - b as f64) < 5.0;
+
+        if !self.is_inside_plot_area(mx, my) {
+            None
+        } else if near(px1, mx) {
+            Some(CursorLine::X1)
+        } else if near(px2, mx) {
+            Some(CursorLine::X2)
+        } else if c.horizontal && near(py1, my) {
+            Some(CursorLine::Y1)
+        } else if c.horizontal && near(py2, my) {
+            Some(CursorLine::Y2)
+        } else {
+            None
+        }
+    }
+
+    pub fn move_cursor_line(&mut self, line: CursorLine, mx: i32, my: i32) {
+        let (x, y) = self.pixel_to_data(mx, my);
+
+        if let Some(c) = self.measure_cursors.borrow_mut().as_mut() {
+            match line {
+                CursorLine::X1 => c.x1 = x,
+                CursorLine::X2 => c.x2 = x,
+                CursorLine::Y1 => c.y1 = y,
+                CursorLine::Y2 => c.y2 = y,
+            }
+        }
+    }
+
+    pub fn set_legend(&mut self, on: bool) {
+        *self.legend.borrow_mut() = on;
+        fltk::app::redraw();
+    }
+
+    // Unnamed series are numbered in the legend and in exported data.
+    pub fn series_label(&self, series: usize) -> String {
+        match self.data.borrow().get(series) {
+            Some(Some(d)) if !d.name.is_empty() => d.name.clone(),
+            _ => format!("Series {}", series + 1),
+        }
+    }
+
+    // Line samples and names of the series in the bottom right corner of the plot area.
+    pub fn draw_legend(&self) {
+        if !*self.legend.borrow() {
+            return;
+        }
+
+        let entries: Vec<(String, LineStyle, i32, Color)> = self
+            .data
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, d)| {
+                d.as_ref()
+                    .filter(|d| d.visible)
+                    .map(|d| (self.series_label(i), d.style, d.width, d.color))
+            })
+            .collect();
+
+        if entries.is_empty() {
+            return;
+        }
+
+        let (x, y, w, h) = self.plot_area();
+        let spc = Graph2DWidget::TEXT_SPC;
+        let line = 5 * spc;
+
+        set_font(Font::Helvetica, Graph2DWidget::TICK_SIZE);
+        let line_height = height();
+        let box_width = entries
+            .iter()
+            .map(|(name, _, _, _)| width(name) as i32)
+            .max()
+            .unwrap_or(0)
+            + line
+            + 3 * spc;
+        let box_height = entries.len() as i32 * line_height + 2 * spc;
+        let bx = x + w - box_width - spc;
+        let by = y + h - box_height - spc;
+
+        draw_rect_fill(bx, by, box_width, box_height, Color::White);
+        draw_rect_with_color(bx, by, box_width, box_height, Color::Black);
+
+        for (i, (name, style, line_width, color)) in entries.iter().enumerate() {
+            let cy = by + spc + i as i32 * line_height + line_height / 2;
+
+            set_draw_color(*color);
+            set_line_style(*style, *line_width);
+            draw_line(bx + spc, cy, bx + spc + line, cy);
+            set_line_style(LineStyle::Solid, 1);
+
+            set_draw_color(Color::Black);
+            draw_text(name, bx + 2 * spc + line, cy + line_height / 2 - descent());
+        }
+    }
+
+    // Draws text lines in a framed box in the top left, or top right, corner of the plot area.
+    pub fn draw_overlay(&self, lines: &[(String, Color)], right: bool) {
+        let (x, y, w, _) = self.plot_area();
+        let spc = Graph2DWidget::TEXT_SPC;
+
+        set_font(Font::Helvetica, Graph2DWidget::TICK_SIZE);
+        let line_height = height();
+        let box_width = lines
+            .iter()
+            .map(|(text, _)| width(text) as i32)
+            .max()
+            .unwrap_or(0)
+            + 2 * spc;
+        let box_height = lines.len() as i32 * line_height + 2 * spc;
+        let x = if right {
+            x + w - box_width - 2 * spc
+        } else {
+            x
+        };
+
+        draw_rect_fill(x + spc, y + spc, box_width, box_height, Color::White);
+        draw_rect_with_color(x + spc, y + spc, box_width, box_height, Color::Black);
+
+        for (i, (text, color)) in lines.iter().enumerate() {
+            set_draw_color(*color);
+            draw_text(
+                text,
+                x + 2 * spc,
+                y + 2 * spc + (i as i32 + 1) * line_height - descent(),
+            );
+        }
+    }
+
+    pub fn draw_measure_cursors(&self) {
+        let c = match *self.measure_cursors.borrow() {
+            Some(c) => c,
+            None => return,
+        };
+
+        let (x, y, w, h) = self.plot_area();
+        let (px1, py1) = self.data_to_pixel(c.x1, c.y1);
+        let (px2, py2) = self.data_to_pixel(c.x2, c.y2);
+        // Positions use the axis formatters, like the data tips and the status line. The
+        // differences are plain numbers.
+        let label = Graph2DWidget::tick_label;
+
+        set_font(Font::Helvetica, Graph2DWidget::TICK_SIZE);
+        set_line_style(LineStyle::Dash, 1);
+        set_draw_color(Color::DarkMagenta);
+        draw_line(px1 as i32, y, px1 as i32, y + h);
+        draw_line(px2 as i32, y, px2 as i32, y + h);
+        draw_text("1", px1 as i32 + 3, y + h - 3);
+        draw_text("2", px2 as i32 + 3, y + h - 3);
+
+        if c.horizontal {
+            draw_line(x, py1 as i32, x + w, py1 as i32);
+            draw_line(x, py2 as i32, x + w, py2 as i32);
+            draw_text("1", x + w - 10, py1 as i32 - 3);
+            draw_text("2", x + w - 10, py2 as i32 - 3);
+        }
+
+        set_line_style(LineStyle::Solid, 1);
+
+        let dx = c.x2 - c.x1;
+        let mut lines = vec![
+            (
+                format!("x1: {}  x2: {}", self.format_x(c.x1), self.format_x(c.x2)),
+                Color::Black,
+            ),
+            (
+                format!(
+                    "\u{0394}x: {}  1/\u{0394}x: {}",
+                    label(dx),
+                    if dx != 0.0 {
+                        label(1.0 / dx)
+                    } else {
+                        String::from("inf")
+                    }
+                ),
+                Color::Black,
+            ),
+        ];
+
+        if c.horizontal {
+            lines.push((
+                format!(
+                    "y1: {}  y2: {}  \u{0394}y: {}",
+                    self.format_y(c.y1),
+                    self.format_y(c.y2),
+                    label(c.y2 - c.y1)
+                ),
+                Color::Black,
+            ));
+        }
+
+        let value = |v: Option<f64>| {
+            v.map(|v| self.format_y(v))
+                .unwrap_or_else(|| String::from("-"))
+        };
+        for (i, v1, v2) in self.measure_values() {
+            let color = match self.data.borrow().get(i) {
+                Some(Some(d)) => d.color,
+                _ => continue,
+            };
+
+            lines.push((
+                format!("{}: {} / {}", self.series_label(i), value(v1), value(v2)),
+                color,
+            ));
+        }
+
+        self.draw_overlay(&lines, false);
+    }
+
+    // While the region tool is enabled, dragging with the left mouse button selects an
+    // x-range instead of zooming.
+    pub fn set_region_tool(&mut self, on: bool) {
+        *self.region_tool.borrow_mut() = on;
+
+        if !on {
+            self.set_stats_region(None);
+        }
+    }
+
+    // Starts selecting a region for the statistics with the region tool.
+    pub fn start_selection(&mut self, mx: i32, my: i32) {
+        if self.is_inside_plot_area(mx, my) {
+            let x = self.pixel_to_data(mx, my).0;
+            *self.selecting_region.borrow_mut() = true;
+            *self.stats_region.borrow_mut() = Some((x, x));
+            fltk::app::redraw();
+        }
+    }
+
+    pub fn drag_selection(&mut self, mx: i32, my: i32) {
+        let x = self.pixel_to_data(mx, my).0;
+        let limit_c = *self.limit_c.borrow();
+        let x = x.clamp(limit_c.x_left, limit_c.x_right);
+
+        if let Some(region) = self.stats_region.borrow_mut().as_mut() {
+            region.1 = x;
+        }
+    }
+
+    // Reports the selected region. A click without dragging clears the selection.
+    pub fn finish_selection(&mut self) {
+        *self.selecting_region.borrow_mut() = false;
+
+        let region = *self.stats_region.borrow();
+        let region = region.filter(|(l, r)| {
+            f64::abs(self.data_to_pixel(*l, 0.0).0 - self.data_to_pixel(*r, 0.0).0) > 2.0
+        });
+        self.set_stats_region(region);
+
+        let region = *self.stats_region.borrow();
+        if let Some((x_left, x_right)) = region {
+            self.emit(PlotEvent::SelectionMade { x_left, x_right });
+        }
+    }
+
+    pub fn set_stats_region(&mut self, region: Option<(f64, f64)>) {
+        *self.stats_region.borrow_mut() = region.map(|(l, r)| (f64::min(l, r), f64::max(l, r)));
+        fltk::app::redraw();
+    }
+
+    // Returns the index and the statistics of each visible series over the selected region.
+    // Series without samples in the region have no statistics.
+    pub fn region_statistics(&self) -> Vec<(usize, Option<RegionStatistics>)> {
+        match *self.stats_region.borrow() {
+            Some((left, right)) => self
+                .data
+                .borrow()
+                .iter()
+                .enumerate()
+                .filter_map(|(i, d)| d.as_ref().filter(|d| d.visible).map(|d| (i, d)))
+                .map(|(i, d)| (i, d.statistics(left, right)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // The selected region is drawn below the grid and the data, like the shaded spans.
+    pub fn draw_stats_region(&self) {
+        let (x_left, x_right) = match *self.stats_region.borrow() {
+            Some(region) => region,
+            None => return,
+        };
+
+        let (x, y, w, h) = self.plot_area();
+        let left = self.data_to_pixel(f64::min(x_left, x_right), 0.0).0 as i32;
+        let right = self.data_to_pixel(f64::max(x_left, x_right), 0.0).0 as i32;
+        let left = i32::max(left, x);
+        let right = i32::min(right, x + w);
+
+        if right > left {
+            draw_rect_fill(
+                left,
+                y,
+                right - left,
+                h,
+                Color::color_average(Color::DarkCyan, Color::White, 0.2),
+            );
+        }
+    }
+
+    pub fn draw_region_statistics(&self) {
+        let (left, right) = match *self.stats_region.borrow() {
+            Some(region) => region,
+            None => return,
+        };
+
+        let label = Graph2DWidget::tick_label;
+        let mut lines = vec![(
+            format!(
+                "x: {} .. {}",
+                label(f64::min(left, right)),
+                label(f64::max(left, right))
+            ),
+            Color::Black,
+        )];
+
+        for (i, stats) in self.region_statistics() {
+            let name = self.series_label(i);
+            let color = match self.data.borrow().get(i) {
+                Some(Some(d)) => d.color,
+                _ => continue,
+            };
+
+            match stats {
+                Some(s) => {
+                    lines.push((
+                        format!(
+                            "{}: min {}  max {}  p-p {}",
+                            name,
+                            label(s.min),
+                            label(s.max),
+                            label(s.peak_to_peak)
+                        ),
+                        color,
+                    ));
+                    lines.push((
+                        format!(
+                            "    mean {}  rms {}  std {}  \u{222B} {}",
+                            label(s.mean),
+                            label(s.rms),
+                            label(s.std_dev),
+                            label(s.integral)
+                        ),
+                        color,
+                    ));
+                }
+                None => lines.push((format!("{}: no samples", name), color)),
+            }
+        }
+
+        self.draw_overlay(&lines, true);
+    }
+
+    pub fn draw_background(&self) {
+        let (x, y, w, h) = self.plot_area();
+        let background = match self.parent() {
+            Some(parent) => parent.color(),
+            None => Color::Background,
+        };
+
+        draw_rect_fill(self.x(), self.y(), self.width(), self.height(), background);
+        draw_rect_fill(x, y, w, h, Color::White);
+    }
+
+    // Grid lines and tick marks are drawn inside the axes box.
+    pub fn draw_grid(&self) {
+        let (x, y, w, h) = self.plot_area();
+        let limit_c = *self.limit_c.borrow();
+        let scales = self.scales();
+        let grid = *self.grid.borrow();
+
+        for v in self.x_ticks() {
+            let px = Graph2DWidget::map_to_pixel((x, y, w, h), &limit_c, scales, v, 0.0).0 as i32;
+
+            if grid {
+                set_draw_color(Color::Light2);
+                set_line_style(LineStyle::Dash, 1);
+                draw_line(px, y, px, y + h);
+            }
+
+            set_draw_color(Color::Black);
+            set_line_style(LineStyle::Solid, 1);
+            draw_line(px, y, px, y + 10);
+            draw_line(px, y + h, px, y + h - 10);
+        }
+
+        for v in self.y_ticks() {
+            let py = Graph2DWidget::map_to_pixel((x, y, w, h), &limit_c, scales, 0.0, v).1 as i32;
+
+            if grid {
+                set_draw_color(Color::Light2);
+                set_line_style(LineStyle::Dash, 1);
+                draw_line(x, py, x + w, py);
+            }
+
+            set_draw_color(Color::Black);
+            set_line_style(LineStyle::Solid, 1);
+            draw_line(x, py, x + 10, py);
+            draw_line(x + w, py, x + w - 10, py);
+        }
+    }
+
+    // The border of the axes box, and the tick labels, caption and axis labels around it.
+    pub fn draw_frame(&self) {
+        let (x, y, w, h) = self.plot_area();
+        let limit_c = *self.limit_c.borrow();
+        let scales = self.scales();
+        let spc = Graph2DWidget::TEXT_SPC;
+
+        set_line_style(LineStyle::Solid, 1);
+        draw_rect_with_color(x, y, w, h, Color::Black);
+
+        set_draw_color(Color::Black);
+        set_font(Font::Helvetica, Graph2DWidget::TICK_SIZE);
+        let tick_height = height();
+
+        for v in self.x_ticks() {
+            let px = Graph2DWidget::map_to_pixel((x, y, w, h), &limit_c, scales, v, 0.0).0 as i32;
+            let text = self.format_x(v);
+
+            draw_text(
+                &text,
+                px - width(&text) as i32 / 2,
+                y + h + spc + tick_height - descent(),
+            );
+        }
+
+        for v in self.y_ticks() {
+            let py = Graph2DWidget::map_to_pixel((x, y, w, h), &limit_c, scales, 0.0, v).1 as i32;
+            let text = self.format_y(v);
+
+            draw_text(
+                &text,
+                x - spc - width(&text) as i32,
+                py + tick_height / 2 - descent(),
+            );
+        }
+
+        set_font(Font::Helvetica, Graph2DWidget::LABEL_SIZE);
+
+        let caption = self.caption.borrow();
+        let text_width = width(caption.as_str()) as i32;
+        draw_text(
+            caption.as_str(),
+            x + w / 2 - text_width / 2,
+            self.y() + spc + height() - descent(),
+        );
+
+        let x_label = self.x_label.borrow();
+        let text_width = width(x_label.as_str()) as i32;
+        draw_text(
+            x_label.as_str(),
+            x + w / 2 - text_width / 2,
+            self.y() + self.height() - spc - descent(),
+        );
+
+        let y_label = self.y_label.borrow();
+        let text_width = width(y_label.as_str()) as i32;
+        draw_text_angled(
+            90,
+            y_label.as_str(),
+            self.x() + spc + height() - descent(),
+            y + h / 2 + text_width / 2,
+        );
+    }
+
+    // Writes all series as rows of series name, x and y.
+    pub fn write_csv(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        writeln!(file, "series,x,y")?;
+
+        for (i, d) in self.data.borrow().iter().enumerate() {
+            if let Some(d) = d {
+                let mut name = self.series_label(i);
+                if name.contains([',', '"']) {
+                    name = format!("\"{}\"", name.replace('"', "\"\""));
+                }
+
+                for j in 0..d.length {
+                    if let Some((x, y)) = d.get_value(j) {
+                        writeln!(file, "{},{},{}", name, x, y)?;
+                    }
+                }
+            }
+        }
+
+        file.flush()
+    }
+
+    pub fn write_svg(&self, path: &Path) -> std::io::Result<()> {
+        // The surface panics if it cannot create the file, so check that first.
+        std::fs::File::create(path)?;
+
+        let surface = SvgFileSurface::new(self.width(), self.height(), path);
+        SvgFileSurface::push_current(&surface);
+        surface.draw(&self.widget.widget, 0, 0);
+        SvgFileSurface::pop_current();
+
+        Ok(())
+    }
+
+    // Copies the plot as SVG markup, which most vector editors and office applications
+    // accept when pasted. FLTK has no clipboard for images and its SVG surface can only
+    // write to a file, so the markup is read back from a temporary one.
+    pub fn copy_svg(&self) -> std::io::Result<()> {
+        let path = std::env::temp_dir().join(format!("fltk-plot-{}.svg", std::process::id()));
+
+        let svg = self
+            .write_svg(&path)
+            .and_then(|_| std::fs::read_to_string(&path));
+        std::fs::remove_file(&path).ok();
+
+        fltk::app::copy(&svg?);
+        Ok(())
+    }
+
+    // Pops up the menu of the subplot at the mouse pointer. The data tip entries act on the
+    // tip or the sample closest to the position the menu was opened at.
+    pub fn context_menu(&mut self, mx: i32, my: i32) {
+        let flag = |on: bool| {
+            if on {
+                MenuFlag::Toggle | MenuFlag::Value
+            } else {
+                MenuFlag::Toggle
+            }
+        };
+        let (x_scale, y_scale) = self.scales();
+        let clicked_tip = self.get_closest_datatip(mx, my);
+
+        let mut menu = MenuButton::default();
+        menu.set_type(MenuButtonType::Popup3);
+
+        let mut graph = self.clone();
+        menu.add("Reset view", Shortcut::None, MenuFlag::Normal, move |_| {
+            graph.reset_view()
+        });
+
+        let mut graph = self.clone();
+        let grid = *self.grid.borrow();
+        menu.add("Grid", Shortcut::None, flag(grid), move |_| {
+            graph.set_grid(!grid)
+        });
+
+        let mut graph = self.clone();
+        let legend = *self.legend.borrow();
+        menu.add(
+            "Legend",
+            Shortcut::None,
+            flag(legend) | MenuFlag::MenuDivider,
+            move |_| graph.set_legend(!legend),
+        );
+
+        let mut graph = self.clone();
+        menu.add(
+            "Axes/Log x",
+            Shortcut::None,
+            flag(x_scale == AxisScale::Log),
+            move |_| {
+                let x = match x_scale {
+                    AxisScale::Linear => AxisScale::Log,
+                    AxisScale::Log => AxisScale::Linear,
+                };
+                graph.set_scales(x, y_scale);
+            },
+        );
+
+        let mut graph = self.clone();
+        menu.add(
+            "Axes/Log y",
+            Shortcut::None,
+            flag(y_scale == AxisScale::Log),
+            move |_| {
+                let y = match y_scale {
+                    AxisScale::Linear => AxisScale::Log,
+                    AxisScale::Log => AxisScale::Linear,
+                };
+                graph.set_scales(x_scale, y);
+            },
+        );
+
+        if *self.mode.borrow() == InteractionMode::EditData {
+            let mut graph = self.clone();
+            menu.add(
+                "Insert point",
+                Shortcut::None,
+                MenuFlag::Normal,
+                move |_| {
+                    graph.insert_point_at(mx, my);
+                },
+            );
+
+            if let Some((series, index)) = self.get_closest_sample(mx, my) {
+                let mut graph = self.clone();
+                menu.add(
+                    "Delete point",
+                    Shortcut::None,
+                    MenuFlag::MenuDivider,
+                    move |_| {
+                        graph.delete_point(series, index);
+                    },
+                );
+            }
+        }
+
+        let mut graph = self.clone();
+        menu.add(
+            "Data tips/Add here",
+            Shortcut::None,
+            MenuFlag::Normal,
+            move |_| {
+                if let Some((series, x, y)) = graph.tip_position(None, mx, my, f64::INFINITY) {
+                    graph.add_data_tip_at(series, x, y);
+                }
+            },
+        );
+
+        if let Some(tip) = clicked_tip {
+            let mut graph = self.clone();
+            menu.add(
+                "Data tips/Remove",
+                Shortcut::None,
+                MenuFlag::Normal,
+                move |_| {
+                    graph.remove_data_tip(tip);
+                },
+            );
+        }
+
+        let mut graph = self.clone();
+        menu.add(
+            "Data tips/Clear all",
+            Shortcut::None,
+            MenuFlag::Normal,
+            move |_| graph.clear_data_tips(),
+        );
+
+        let labels = [
+            ("Labels/Caption...", "Caption:", self.caption.clone()),
+            ("Labels/X label...", "X label:", self.x_label.clone()),
+            ("Labels/Y label...", "Y label:", self.y_label.clone()),
+        ];
+        for (name, prompt, label) in labels {
+            menu.add(name, Shortcut::None, MenuFlag::Normal, move |_| {
+                let current = label.borrow().clone();
+                if let Some(text) = input_default(prompt, &current) {
+                    *label.borrow_mut() = text;
+                    fltk::app::redraw();
+                }
+            });
+        }
+
+        let graph = self.clone();
+        menu.add(
+            "Properties...",
+            Shortcut::None,
+            MenuFlag::MenuDivider,
+            move |_| {
+                show_property_dialog(&graph);
+            },
+        );
+
+        let graph = self.clone();
+        menu.add("Copy as SVG", Shortcut::None, MenuFlag::Normal, move |_| {
+            if let Err(e) = graph.copy_svg() {
+                alert_default(&format!("Could not copy plot: {}", e));
+            }
+        });
+
+        let graph = self.clone();
+        menu.add(
+            "Export data as CSV...",
+            Shortcut::None,
+            MenuFlag::Normal,
+            move |_| {
+                let mut chooser = FileDialog::new(FileDialogType::BrowseSaveFile);
+                chooser.set_option(FileDialogOptions::SaveAsConfirm);
+                chooser.set_filter("*.csv");
+                chooser.show();
+
+                let path = chooser.filename();
+                if !path.as_os_str().is_empty() {
+                    if let Err(e) = graph.write_csv(&path) {
+                        alert_default(&format!("Could not export data: {}", e));
+                    }
+                }
+            },
+        );
+
+        menu.popup();
+        MenuButton::delete(menu);
+    }
+
+    // Binding MouseAction::None to a button leaves it to the embedding application.
+    pub fn bind_mouse(&mut self, binding: MouseBinding) {
+        let mut bindings = self.bindings.borrow_mut();
+
+        bindings.retain(|b| {
+            b.button != binding.button
+                || b.modifiers != binding.modifiers
+                || b.gesture != binding.gesture
+        });
+        bindings.push(binding);
+    }
+
+    pub fn reset_mouse_bindings(&mut self) {
+        *self.bindings.borrow_mut() = MouseBinding::defaults();
+    }
+
+    pub fn find_mouse_action(&self, gesture: Gesture) -> MouseAction {
+        let modifiers = fltk::app::event_state()
+            & (Shortcut::Shift | Shortcut::Ctrl | Shortcut::Alt | Shortcut::Meta);
+
+        MouseBinding::find(
+            &self.bindings.borrow(),
+            fltk::app::event_mouse_button(),
+            modifiers,
+            gesture,
+        )
+    }
+
+    pub fn remove_data_tip(&mut self, index: usize) -> Option<DataTip> {
+        if index >= self.data_tips.borrow().len() {
+            return None;
+        }
+
+        let mut selected = self.selected_tip.borrow_mut();
+        *selected = match *selected {
+            Some(s) if s == index => None,
+            Some(s) if s > index => Some(s - 1),
+            s => s,
+        };
+        drop(selected);
+
+        let tip = self.data_tips.borrow_mut().remove(index);
+        self.emit(PlotEvent::DataTipRemoved { index, tip });
+
+        fltk::app::redraw();
+        Some(tip)
+    }
+
+    // Moves a data tip to another position, normally a point of its series.
+    pub fn move_data_tip(&mut self, index: usize, x: f64, y: f64) {
+        let tip = match self.data_tips.borrow_mut().get_mut(index) {
+            Some(tip) if tip.x != x || tip.y != y => {
+                tip.x = x;
+                tip.y = y;
+                *tip
+            }
+            _ => return,
+        };
+
+        self.emit(PlotEvent::DataTipMoved { index, tip });
+        fltk::app::redraw();
+    }
+
+    // Selects the next data tip, or the previous one for a negative step, wrapping around.
+    pub fn select_next_tip(&mut self, step: isize) {
+        let len = self.data_tips.borrow().len() as isize;
+        let mut selected = self.selected_tip.borrow_mut();
+
+        *selected = if len == 0 {
+            None
+        } else {
+            match *selected {
+                Some(s) => Some((s as isize + step).rem_euclid(len) as usize),
+                None if step < 0 => Some(len as usize - 1),
+                None => Some(0),
+            }
+        };
+
+        fltk::app::redraw();
+    }
+
+    // Keyboard navigation of the view and the data tips, returns whether the key was used.
+    pub fn handle_key(&mut self, key: Key, text: &str, alt: bool, shift: bool) -> bool {
+        let selected = *self.selected_tip.borrow();
+        let before = *self.widget.limit_c.borrow();
+        let (x, y, w, h) = self.plot_area();
+        let (cx, cy) = (x + w / 2, y + h / 2);
+
+        // Stepping through the history must not add to it. The keys are only
+        // taken if there is a view to step to.
+        if alt && key == Key::Left {
+            return self.view_back();
+        } else if alt && key == Key::Right {
+            return self.view_forward();
+        } else if let (Some(tip), Key::Left | Key::Right) = (selected, key) {
+            // With a selected data tip, left and right step it along its series.
+            self.step_data_tip(tip, if key == Key::Left { -1 } else { 1 });
+        } else if key == Key::Left {
+            self.pan_view(-0.1, 0.0);
+        } else if key == Key::Right {
+            self.pan_view(0.1, 0.0);
+        } else if key == Key::Up {
+            self.pan_view(0.0, 0.1);
+        } else if key == Key::Down {
+            self.pan_view(0.0, -0.1);
+        } else if text == "+" || text == "=" {
+            self.zoom_at(cx, cy, 0.2, ZoomAxis::Both);
+        } else if text == "-" {
+            self.zoom_at(cx, cy, -0.2, ZoomAxis::Both);
+        } else if key == Key::Home {
+            self.reset_view();
+        } else if key == Key::Tab && !self.data_tips.borrow().is_empty() {
+            self.select_next_tip(if shift { -1 } else { 1 });
+        } else if let (Some(tip), Key::Delete | Key::BackSpace) = (selected, key) {
+            self.remove_data_tip(tip);
+        } else if key == Key::Escape && *self.widget.zooming.borrow() {
+            *self.widget.zooming.borrow_mut() = false;
+        } else if key == Key::Escape && selected.is_some() {
+            *self.selected_tip.borrow_mut() = None;
+        } else {
+            // Leave other keys, like Escape to close the window, to FLTK.
+            return false;
+        }
+
+        self.record_view(before);
+        fltk::app::redraw();
+
+        true
+    }
+
+    // Moves a data tip to the previous or next sample of its series.
+    pub fn step_data_tip(&mut self, index: usize, step: isize) {
+        let value = {
+            let data_tips = self.data_tips.borrow();
+            let data = self.data.borrow();
+
+            data_tips.get(index).and_then(|tip| {
+                let d = data.get(tip.plot_idx)?.as_ref()?;
+                let (sample, _) = Graph2DWidget::tip_sample(tip, d)?;
+
+                d.get_value(sample.checked_add_signed(step)?)
+            })
+        };
+
+        if let Some((x, y)) = value {
+            self.move_data_tip(index, x, y);
+        }
+    }
+
+    // Moves a data tip to a sample of its series.
+    pub fn move_data_tip_to_sample(&mut self, index: usize, sample: usize) {
+        let value = {
+            let data_tips = self.data_tips.borrow();
+            let data = self.data.borrow();
+
+            data_tips
+                .get(index)
+                .and_then(|tip| data.get(tip.plot_idx)?.as_ref()?.get_value(sample))
+        };
+
+        if let Some((x, y)) = value {
+            self.move_data_tip(index, x, y);
+        }
+    }
+
+    // Sets the pixel offset of the label of a data tip from its marker.
+    pub fn set_tip_label_offset(&mut self, index: usize, lx: i32, ly: i32) {
+        if let Some(tip) = self.data_tips.borrow_mut().get_mut(index) {
+            tip.lx = lx;
+            tip.ly = ly;
+            tip.auto_place = false;
+        }
+
+        fltk::app::redraw();
+    }
+
+    // Lets the label of a data tip be placed automatically again after it was moved by hand.
+    pub fn set_tip_auto_place(&mut self, index: usize, on: bool) {
+        if let Some(tip) = self.data_tips.borrow_mut().get_mut(index) {
+            tip.auto_place = on;
+        }
+
+        fltk::app::redraw();
+    }
+
+    pub fn set_tip_style(&mut self, index: usize, style: TipStyle) {
+        if let Some(tip) = self.data_tips.borrow_mut().get_mut(index) {
+            tip.style = style;
+        }
+
+        fltk::app::redraw();
+    }
+
+    // The style given to new data tips, existing tips keep their own.
+    pub fn set_default_tip_style(&mut self, style: TipStyle) {
+        *self.tip_style.borrow_mut() = style;
+    }
+
+    // Adds a data tip on a sample of a series and selects it. Returns the index of the tip.
+    pub fn add_data_tip(&mut self, series: usize, index: usize) -> Option<usize> {
+        let (x, y) = self.data.borrow().get(series)?.as_ref()?.get_value(index)?;
+
+        self.add_data_tip_at(series, x, y)
+    }
+
+    // Adds a data tip at any position of a series, normally one returned by tip_position.
+    pub fn add_data_tip_at(&mut self, series: usize, x: f64, y: f64) -> Option<usize> {
+        self.data.borrow().get(series)?.as_ref()?;
+        let mut data_tips = self.data_tips.borrow_mut();
+
+        let tip = DataTip {
+            x,
+            y,
+            plot_idx: series,
+            lx: 10,
+            ly: -10,
+            style: *self.tip_style.borrow(),
+            auto_place: true,
+        };
+        data_tips.push(tip);
+
+        let index = data_tips.len() - 1;
+        *self.selected_tip.borrow_mut() = Some(index);
+        drop(data_tips);
+
+        self.emit(PlotEvent::DataTipAdded { index, tip });
+        fltk::app::redraw();
+        Some(index)
+    }
+
+    pub fn clear_data_tips(&mut self) {
+        let count = self.data_tips.borrow().len();
+        for index in (0..count).rev() {
+            self.remove_data_tip(index);
+        }
+
+        *self.closest_data_tip.borrow_mut() = None;
+    }
+
+    // Returns the sample a data tip is on, and whether the tip is between samples instead. The
+    // sample closest in x is taken for those.
+    fn tip_sample(tip: &DataTip, d: &Plot2DData) -> Option<(usize, bool)> {
+        if let Some(i) = (0..d.length).find(|i| d.x[*i] == tip.x && d.y[*i] == tip.y) {
+            return Some((i, false));
+        }
+
+        (0..d.length)
+            .filter(|i| d.x[*i].is_finite())
+            .min_by(|a, b| f64::abs(d.x[*a] - tip.x).total_cmp(&f64::abs(d.x[*b] - tip.x)))
+            .map(|i| (i, true))
+    }
+
+    // Sets the formatter of the data tip labels of a series, or the default one for None.
+    pub fn set_tip_formatter(&mut self, series: usize, formatter: Option<TipFormatter>) {
+        match formatter {
+            Some(f) => self.tip_formatters.borrow_mut().insert(series, f),
+            None => self.tip_formatters.borrow_mut().remove(&series),
+        };
+
+        fltk::app::redraw();
+    }
+
+    pub fn set_tip_snap(&mut self, snap: TipSnap) {
+        *self.tip_snap.borrow_mut() = snap;
+    }
+
+    pub fn tip_text(&self, tip: &DataTip) -> String {
+        let default = || format!("x: {:.2} y: {:.2}", tip.x, tip.y);
+        let Some(formatter) = self.tip_formatters.borrow().get(&tip.plot_idx).cloned() else {
+            return default();
+        };
+
+        let data = self.data.borrow();
+        let Some(d) = data.get(tip.plot_idx).and_then(|d| d.as_ref()) else {
+            return default();
+        };
+        let (index, interpolated) = Graph2DWidget::tip_sample(tip, d).unwrap_or((0, true));
+
+        formatter(&TipInfo {
+            name: &d.name,
+            index,
+            x: tip.x,
+            y: tip.y,
+            interpolated,
+            metadata: d.get_metadata(index),
+        })
+    }
+
+    // The labels are placed before anything is drawn, so that the leader lines stay below
+    // all labels and the markers on top of them.
+    pub fn draw_data_tips(&self) {
+        let area = self.plot_area();
+        let (x, y, w, h) = area;
+        let limit_c = *self.limit_c.borrow();
+        let scales = self.scales();
+        let pad = Graph2DWidget::TIP_PADDING;
+
+        set_font(Font::Helvetica, Graph2DWidget::TICK_SIZE);
+        set_line_style(LineStyle::Solid, 1);
+
+        let line_height = height();
+        // From the top of a label box to the baseline of its first line.
+        let baseline = pad + line_height - descent();
+
+        // The tips whose marker is in view, with the marker position and the label lines.
+        let mut tips = Vec::new();
+        for (i, tip) in self.data_tips.borrow().iter().enumerate() {
+            let (px, py) = Graph2DWidget::map_to_pixel(area, &limit_c, scales, tip.x, tip.y);
+            let px = px as i32;
+            let py = py as i32;
+
+            if px >= x && px <= (x + w) && py >= y && py <= (y + h) {
+                let lines: Vec<String> = self.tip_text(tip).lines().map(String::from).collect();
+                tips.push((i, *tip, px, py, lines));
+            }
+        }
+
+        let mut occupancy = Occupancy::new(area);
+        for d in self.data.borrow().iter().flatten().filter(|d| d.visible) {
+            let mut last = None;
+
+            for i in 0..d.length {
+                let p = Graph2DWidget::map_to_pixel(area, &limit_c, scales, d.x[i], d.y[i]);
+                occupancy.mark_point(p.0, p.1);
+                if let Some(last) = last {
+                    occupancy.mark_line(last, p);
+                }
+                last = Some(p);
+            }
+        }
+        for (_, tip, px, py, _) in tips.iter() {
+            let r = tip.style.marker_size / 2 + 1;
+            occupancy.mark_rect((px - r, py - r, 2 * r, 2 * r));
+        }
+
+        // Labels that are placed by hand are only kept inside the plot area.
+        let mut boxes = Vec::with_capacity(tips.len());
+        for (_, tip, px, py, lines) in tips.iter() {
+            let tw = lines.iter().map(|l| measure(l, false).0).max().unwrap_or(0);
+            let size = (tw + 2 * pad, line_height * lines.len() as i32 + 2 * pad);
+
+            let rect = if tip.auto_place {
+                place_label((*px, *py), tip.style.marker_size / 2, size, &occupancy)
+            } else {
+                let rect = (px + tip.lx - pad, py + tip.ly - baseline, size.0, size.1);
+                clamp_rect(rect, area)
+            };
+
+            occupancy.mark_rect(rect);
+            boxes.push(rect);
+        }
+
+        for (&(_, tip, px, py, _), &(bx, by, bw, bh)) in tips.iter().zip(boxes.iter()) {
+            let end = (px.clamp(bx, bx + bw), py.clamp(by, by + bh));
+
+            if tip.style.leader_line && end != (px, py) {
+                set_draw_color(Color::Dark3);
+                draw_line(px, py, end.0, end.1);
+            }
+        }
+
+        for ((_, tip, _, _, lines), &(bx, by, bw, bh)) in tips.iter().zip(boxes.iter()) {
+            if let Some(background) = tip.style.background {
+                draw_rect_fill(bx, by, bw, bh, background);
+            }
+            draw_rect_with_color(bx, by, bw, bh, Color::Black);
+
+            set_draw_color(Color::Black);
+            for (k, line) in lines.iter().enumerate() {
+                draw_text(line, bx + pad, by + baseline + k as i32 * line_height);
+            }
+        }
+
+        let selected = *self.selected_tip.borrow();
+        for (i, tip, px, py, _) in tips.iter() {
+            if selected == Some(*i) {
+                let r = tip.style.marker_size / 2 + 2;
+                draw_rect_with_color(px - r, py - r, 2 * r, 2 * r, Color::Red);
+            }
+
+            Graph2DWidget::draw_tip_marker(*px, *py, &tip.style);
+        }
+    }
+
+    fn draw_tip_marker(px: i32, py: i32, style: &TipStyle) {
+        let size = style.marker_size;
+        let r = size / 2;
+
+        set_draw_color(style.marker_color);
+        match style.marker {
+            TipMarker::Square => draw_rect_fill(px - r, py - r, size, size, style.marker_color),
+            TipMarker::Circle => draw_pie(px - r, py - r, size, size, 0.0, 360.0),
+            TipMarker::Cross => {
+                set_line_style(LineStyle::Solid, 2);
+                draw_line(px - r, py - r, px + r, py + r);
+                draw_line(px - r, py + r, px + r, py - r);
+                set_line_style(LineStyle::Solid, 1);
+            }
+            TipMarker::None => {}
+        }
+    }
+
+    // Zooming along one axis only draws a box that spans the plot area along the other.
+    pub fn draw_zoom_box(&self) {
+        if *self.zooming.borrow() {
+            let (x, y, w, h) = self.plot_area();
+            let mut zoom_x = *self.zoom_x.borrow();
+            let mut zoom_y = *self.zoom_y.borrow();
+            let mut dx = fltk::app::event_x() - zoom_x;
+            let mut dy = fltk::app::event_y() - zoom_y;
+
+            match *self.zoom_axis.borrow() {
+                ZoomAxis::X => {
+                    zoom_y = y;
+                    dy = h;
+                }
+                ZoomAxis::Y => {
+                    zoom_x = x;
+                    dx = w;
+                }
+                ZoomAxis::Both => {}
+            }
+
+            set_line_style(LineStyle::Dash, 1);
+            draw_rect_with_color(
+                i32::min(zoom_x, zoom_x + dx),
+                i32::min(zoom_y, zoom_y + dy),
+                i32::abs(dx),
+                i32::abs(dy),
+                Color::Black,
+            );
+            set_line_style(LineStyle::Solid, 1);
+        }
+    }
+
+    // Returns a data tip on the point closest to the mouse pointer, if it is within 10
+    // pixels. The tip has no label offset yet.
+    pub fn get_closest_point(&self, mx: i32, my: i32) -> Option<DataTip> {
+        let (series, index) = self.get_closest_sample(mx, my)?;
+        let (x, y) = self.data.borrow()[series].as_ref()?.get_value(index)?;
+
+        Some(DataTip {
+            x,
+            y,
+            plot_idx: series,
+            ..DataTip::default()
+        })
+    }
+
+    // Returns the data tip whose marker is under the mouse pointer.
+    pub fn get_closest_datatip(&self, mx: i32, my: i32) -> Option<usize> {
+        self.data_tips.borrow().iter().position(|tip| {
+            let (px, py) = self.data_to_pixel(tip.x, tip.y);
+
+            i32::max(i32::abs(px as i32 - mx), i32::abs(py as i32 - my)) < 15
+        })
+    }
+}
+
+impl MyWidget for Graph2DWidget {
+    fn put_data(&mut self, x: &[f64], y: &[f64], style: LineStyle, width: i32, color: Color) {
+        let data = Plot2DData::new_xy(x, y, style, width, color);
+
+        if !*self.hold.borrow() {
+            self.data.borrow_mut().clear();
+            self.patches.borrow_mut().clear();
+        }
+
+        let (x_min, x_max) = data.get_x_limit();
+        let (y_min, y_max) = data.get_y_limit();
+
+        self.update_limit(x_min, x_max, y_min, y_max);
+
+        self.data.borrow_mut().push(Some(data));
+    }
+
+    fn handle(&mut self) {
+        let wid = self.widget.clone();
+        let closest_data_tip = self.closest_data_tip.clone();
+        let data_tips = self.data_tips.clone();
+        let closest_annotation = self.closest_annotation.clone();
+        let mut graph = self.clone();
+
+        self.widget.widget.handle(move |_, event| {
+            let (mx, my) = fltk::app::event_coords();
+            let (plot_x, plot_y, plot_width, plot_height) = graph.plot_area();
+
+            match event {
+                Event::Push => {
+                    let drag_action = graph.find_mouse_action(Gesture::Drag);
+                    *graph.drag_action.borrow_mut() = drag_action;
+                    *graph.click_action.borrow_mut() = graph.find_mouse_action(Gesture::Click);
+                    *graph.press_pos.borrow_mut() = (mx, my);
+                    *graph.view_start.borrow_mut() = Some(*wid.limit_c.borrow());
+                    graph.widget.widget.take_focus().ok();
+                    *closest_data_tip.borrow_mut() = graph.get_closest_datatip(mx, my);
+
+                    match drag_action {
+                        MouseAction::Tool | MouseAction::ZoomBox | MouseAction::DataTip => {
+                            let mode = match drag_action {
+                                MouseAction::ZoomBox => InteractionMode::ZoomBox,
+                                MouseAction::DataTip => InteractionMode::DataCursor,
+                                _ => *graph.mode.borrow(),
+                            };
+
+                            // Measurement cursors take precedence over data tips.
+                            *graph.dragged_cursor.borrow_mut() =
+                                graph.get_closest_cursor_line(mx, my);
+
+                            if !matches!(mode, InteractionMode::Auto | InteractionMode::DataCursor)
+                            {
+                                *closest_data_tip.borrow_mut() = None;
+                            }
+
+                            // First check if user clicked on a data tip.
+
+                            // Find a data tip point close to the mouse pointer.
+                            if graph.dragged_cursor.borrow().is_some() {
+                                *closest_data_tip.borrow_mut() = None;
+                            } else if let Some(axis) = graph.axis_region(mx, my) {
+                                // Dragging the tick labels pans along that axis only.
+                                *closest_data_tip.borrow_mut() = None;
+                                *graph.panning.borrow_mut() = true;
+                                graph.start_pan(mx, my, axis);
+                            } else if *graph.region_tool.borrow()
+                                && drag_action == MouseAction::Tool
+                            {
+                                *closest_data_tip.borrow_mut() = None;
+                                graph.start_selection(mx, my);
+                            } else if matches!(
+                                mode,
+                                InteractionMode::ZoomBox
+                                    | InteractionMode::ZoomX
+                                    | InteractionMode::ZoomY
+                            ) {
+                                if graph.is_inside_plot_area(mx, my) {
+                                    graph.start_zoom(mx, my);
+                                }
+                            } else if mode == InteractionMode::Pan {
+                                *graph.panning.borrow_mut() = true;
+                                graph.start_pan(mx, my, ZoomAxis::Both);
+                            } else if mode == InteractionMode::EditData {
+                                let sample = graph.get_closest_sample(mx, my);
+
+                                // Double-clicking a point deletes it, double-clicking elsewhere
+                                // adds a point to the closest series.
+                                if !fltk::app::event_clicks() {
+                                    *graph.edited_point.borrow_mut() = sample;
+                                } else if let Some((series, index)) = sample {
+                                    graph.delete_point(series, index);
+                                } else if graph.is_inside_plot_area(mx, my) {
+                                    graph.insert_point_at(mx, my);
+                                }
+                            } else if mode == InteractionMode::Measure {
+                                // Only the cursor lines can be dragged in this mode.
+                            } else if closest_data_tip.borrow().is_none() {
+                                // User didn't click on an existing data tip.
+                                if let Some((series, x, y)) = graph.tip_position(None, mx, my, 10.0)
+                                {
+                                    graph.add_data_tip_at(series, x, y);
+                                } else if mode == InteractionMode::Auto {
+                                    graph.start_zoom(mx, my);
+                                    *graph.selected_tip.borrow_mut() = None;
+                                }
+
+                                fltk::app::redraw();
+                            }
+
+                            // Clicking an existing data tip selects it for the keyboard.
+                            let clicked_tip = *closest_data_tip.borrow();
+                            if clicked_tip.is_some() {
+                                *graph.selected_tip.borrow_mut() = clicked_tip;
+                            }
+                        }
+                        MouseAction::Pan => {
+                            let axis = graph.axis_region(mx, my).unwrap_or(ZoomAxis::Both);
+                            graph.start_pan(mx, my, axis);
+
+                            // Data tip labels take precedence over annotations.
+                            *closest_annotation.borrow_mut() =
+                                if closest_data_tip.borrow().is_none() {
+                                    graph.get_closest_annotation(mx, my)
+                                } else {
+                                    None
+                                };
+                        }
+                        MouseAction::Reset | MouseAction::ContextMenu | MouseAction::None => {}
+                    }
+
+                    true
+                }
+                Event::Drag => {
+                    let drag_action = *graph.drag_action.borrow();
+
+                    graph.track_pointer(Some((mx, my)));
+                    graph.emit_mouse_move(mx, my);
+
+                    match drag_action {
+                        MouseAction::Tool | MouseAction::ZoomBox | MouseAction::DataTip => {
+                            let dragged_cursor = *graph.dragged_cursor.borrow();
+                            let edited_point = *graph.edited_point.borrow();
+
+                            if let Some(line) = dragged_cursor {
+                                graph.move_cursor_line(line, mx, my);
+                            } else if *graph.panning.borrow() {
+                                graph.pan_to(mx, my);
+                            } else if let Some((series, index)) = edited_point {
+                                graph.move_point(series, index, mx, my);
+                            } else if *graph.selecting_region.borrow() {
+                                graph.drag_selection(mx, my);
+                            } else if let (false, Some(tip_idx)) =
+                                (*wid.zooming.borrow(), *closest_data_tip.borrow())
+                            {
+                                // The data tip follows the mouse along its own series.
+                                let series = data_tips.borrow().get(tip_idx).map(|t| t.plot_idx);
+                                let position = series.and_then(|s| {
+                                    graph.tip_position(Some(s), mx, my, f64::INFINITY)
+                                });
+
+                                if let Some((_, x, y)) = position {
+                                    graph.move_data_tip(tip_idx, x, y);
+                                }
+                            }
+                        }
+                        MouseAction::Pan => {
+                            if closest_data_tip.borrow().is_some() {
+                                if let Some(tip_idx) = *closest_data_tip.borrow() {
+                                    if let Some(tip) = data_tips.borrow_mut().get_mut(tip_idx) {
+                                        let (px, py) = graph.data_to_pixel(tip.x, tip.y);
+
+                                        tip.lx = mx - px as i32;
+                                        tip.ly = my - py as i32;
+                                        tip.auto_place = false;
+                                    }
+                                }
+                            } else if let Some(idx) = *closest_annotation.borrow() {
+                                let from = (*wid.zoom_x.borrow(), *wid.zoom_y.borrow());
+                                graph.move_annotation(idx, from, (mx, my));
+
+                                *wid.zoom_x.borrow_mut() = mx;
+                                *wid.zoom_y.borrow_mut() = my;
+                            } else {
+                                graph.pan_to(mx, my);
+                            }
+                        }
+                        _ => {}
+                    }
+
+                    fltk::app::redraw();
+
+                    true
+                }
+                Event::Released => {
+                    let (px, py) = *graph.press_pos.borrow();
+                    let click_action = *graph.click_action.borrow();
+                    let drag_action = *graph.drag_action.borrow();
+                    let clicked = i32::abs(mx - px) <= 2 && i32::abs(my - py) <= 2;
+
+                    *graph.dragged_cursor.borrow_mut() = None;
+                    *graph.panning.borrow_mut() = false;
+                    let edited_point = graph.edited_point.borrow_mut().take();
+                    *graph.drag_action.borrow_mut() = MouseAction::None;
+                    *graph.click_action.borrow_mut() = MouseAction::None;
+
+                    if let (Some((series, _)), false) = (edited_point, clicked) {
+                        graph.data_changed(series);
+                    }
+
+                    // The search for the point under the mouse pointer is skipped at the end
+                    // of drags.
+                    if clicked && Graph2DWidget::is_tool_click(drag_action, click_action) {
+                        graph.click_point(mx, my);
+                    }
+
+                    let clicked_tip = *closest_data_tip.borrow();
+                    match click_action {
+                        MouseAction::Reset if clicked => {
+                            if let Some(tip) = clicked_tip {
+                                graph.remove_data_tip(tip);
+                            } else {
+                                graph.reset_view();
+                            }
+                        }
+                        MouseAction::DataTip if clicked && clicked_tip.is_none() => {
+                            if let Some((series, x, y)) = graph.tip_position(None, mx, my, 10.0) {
+                                graph.add_data_tip_at(series, x, y);
+                            }
+                        }
+                        MouseAction::ContextMenu if clicked => graph.context_menu(mx, my),
+                        _ => {}
+                    }
+
+                    if *graph.selecting_region.borrow() {
+                        graph.finish_selection();
+                    }
+
+                    if *wid.zooming.borrow() {
+                        let zoom_x = *wid.zoom_x.borrow();
+                        let zoom_y = *wid.zoom_y.borrow();
+                        let zoom_axis = *graph.zoom_axis.borrow();
+                        let dx = i32::abs(mx - zoom_x);
+                        let dy = i32::abs(my - zoom_y);
+                        *wid.zooming.borrow_mut() = false;
+
+                        let zoom = match zoom_axis {
+                            ZoomAxis::X => dx > 2,
+                            ZoomAxis::Y => dy > 2,
+                            ZoomAxis::Both => dx > 2 || dy > 2,
+                        };
+
+                        if zoom {
+                            // Clip to the plot area, the corners give the new limits.
+                            let left = i32::max(i32::min(zoom_x, mx), plot_x);
+                            let right = i32::min(i32::max(zoom_x, mx), plot_x + plot_width);
+                            let top = i32::max(i32::min(zoom_y, my), plot_y);
+                            let bottom = i32::min(i32::max(zoom_y, my), plot_y + plot_height);
+                            let (x_left, y_left) = graph.pixel_to_data(left, bottom);
+                            let (x_right, y_right) = graph.pixel_to_data(right, top);
+
+                            // Zooming along one axis keeps the view of the other.
+                            let mut limit_c = wid.limit_c.borrow_mut();
+                            if zoom_axis != ZoomAxis::Y {
+                                limit_c.x_left = x_left;
+                                limit_c.x_right = x_right;
+                            }
+                            if zoom_axis != ZoomAxis::X {
+                                limit_c.y_left = y_left;
+                                limit_c.y_right = y_right;
+                            }
+                            drop(limit_c);
+
+                            graph.propagate_limits();
+                        }
+
+                        fltk::app::redraw();
+                    }
+
+                    // Every zoom and pan done with the mouse ends with a release.
+                    let view_start = graph.view_start.borrow_mut().take();
+                    if let Some(before) = view_start {
+                        graph.record_view(before);
+                    }
+
+                    true
+                }
+                Event::MouseWheel => {
+                    graph.handle_wheel(
+                        mx,
+                        my,
+                        fltk::app::event_dx_value() as f64,
+                        fltk::app::event_dy_value() as f64,
+                        fltk::app::is_event_shift(),
+                        fltk::app::is_event_ctrl(),
+                    );
+
+                    true
+                }
+                Event::Focus | Event::Unfocus => true,
+                Event::KeyDown => graph.handle_key(
+                    fltk::app::event_key(),
+                    &fltk::app::event_text(),
+                    fltk::app::is_event_alt(),
+                    fltk::app::is_event_shift(),
+                ),
+                Event::Enter => true,
+                Event::Show | Event::Hide => {
+                    graph.set_linked(event == Event::Show);
+                    false
+                }
+                Event::Move => {
+                    graph.track_pointer(Some((mx, my)));
+                    graph.emit_mouse_move(mx, my);
+
+                    if let Some(cursor) = graph.sync_cursor.borrow().as_ref() {
+                        *cursor.x.borrow_mut() = if graph.is_inside_plot_area(mx, my) {
+                            Some(graph.pixel_to_data(mx, my).0)
+                        } else {
+                            None
+                        };
+
+                        fltk::app::redraw();
+                    }
+
+                    true
+                }
+                Event::Leave => {
+                    graph.track_pointer(None);
+                    if let Some(cursor) = graph.sync_cursor.borrow().as_ref() {
+                        *cursor.x.borrow_mut() = None;
+                        fltk::app::redraw();
+                    }
+
+                    true
+                }
+                _ => false,
+            }
+        });
+    }
+
+    fn set_grid(&mut self, on: bool) {
+        self.widget.set_grid(on);
+    }
+
+    fn set_x_label(&mut self, label: &str) {
+        self.widget.set_x_label(label);
+    }
+
+    fn set_y_label(&mut self, label: &str) {
+        self.widget.set_y_label(label);
+    }
+
+    fn set_caption(&mut self, caption: &str) {
+        self.widget.set_caption(caption);
+    }
+
+    fn set_hold(&mut self, hold: bool) {
+        self.widget.set_hold(hold);
+    }
+
+    fn graph_2d(&self) -> Option<Graph2DWidget> {
+        Some(self.clone())
+    }
+}
+
+impl Deref for Graph2DWidget {
+    type Target = GraphWidget;
+
+    fn deref(&self) -> &Self::Target {
+        &self.widget
+    }
+}
+
+impl DerefMut for Graph2DWidget {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.widget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{close, limit, on_ui_thread};
+
+    const AREA: (i32, i32, i32, i32) = (10, 20, 100, 60);
+
+    // A graph whose plot area is the whole widget, (0, 0, 100, 100), with a view of 0 to 10
+    // on both axes: a pixel is a tenth of a data unit and y grows up from pixel row 100.
+    fn graph() -> Graph2DWidget {
+        let mut graph = Graph2DWidget::new(0, 0, 100, 100, "");
+        *graph.margins.borrow_mut() = (0, 0, 0, 0);
+        graph.put_data(
+            &[0.0, 5.0, 10.0],
+            &[0.0, 5.0, 10.0],
+            LineStyle::Solid,
+            1,
+            Color::Black,
+        );
+        graph
+    }
+
+    // Collects the events the graph emits.
+    fn record_events(graph: &mut Graph2DWidget) -> Rc<RefCell<Vec<PlotEvent>>> {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let sink = events.clone();
+        graph.set_event_callback(Some(Rc::new(move |e: &PlotEvent| {
+            sink.borrow_mut().push(*e)
+        })));
+        events
+    }
+
+    // The graph above with a second, hidden series from 10 down to 0.
+    fn graph_with_hidden_series() -> Graph2DWidget {
+        let mut graph = graph();
+        graph.set_hold(true);
+        graph.put_data(&[0.0, 10.0], &[10.0, 0.0], LineStyle::Solid, 1, Color::Red);
+        if let Some(d) = graph.data.borrow_mut()[1].as_mut() {
+            d.visible = false;
+        }
+        graph
+    }
+
+    #[test]
+    fn map_to_pixel_linear() {
+        let scales = (AxisScale::Linear, AxisScale::Linear);
+        let l = limit(0.0, 10.0, 0.0, 6.0);
+
+        assert_eq!(
+            Graph2DWidget::map_to_pixel(AREA, &l, scales, 5.0, 3.0),
+            (60.0, 50.0)
+        );
+        assert_eq!(
+            Graph2DWidget::map_to_pixel(AREA, &l, scales, 0.0, 0.0),
+            (10.0, 80.0)
+        );
+        assert_eq!(
+            Graph2DWidget::map_to_pixel(AREA, &l, scales, 10.0, 6.0),
+            (110.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn map_to_pixel_log() {
+        let scales = (AxisScale::Log, AxisScale::Log);
+        let l = limit(1.0, 100.0, 1.0, 1000.0);
+        let (px, py) = Graph2DWidget::map_to_pixel(AREA, &l, scales, 10.0, 10.0);
+
+        assert!(close(px, 60.0));
+        assert!(close(py, 60.0));
+    }
+
+    #[test]
+    fn map_to_pixel_log_of_non_positive_values() {
+        let scales = (AxisScale::Log, AxisScale::Linear);
+        let l = limit(1.0, 100.0, 0.0, 1.0);
+
+        assert!(!Graph2DWidget::map_to_pixel(AREA, &l, scales, 0.0, 0.5)
+            .0
+            .is_finite());
+        assert!(!Graph2DWidget::map_to_pixel(AREA, &l, scales, -1.0, 0.5)
+            .0
+            .is_finite());
+    }
+
+    #[test]
+    fn linear_ticks_divide_the_axis() {
+        assert_eq!(
+            Graph2DWidget::axis_ticks(AxisScale::Linear, 0.0, 10.0, 4),
+            vec![2.0, 4.0, 6.0, 8.0]
+        );
+        assert!(Graph2DWidget::axis_ticks(AxisScale::Linear, 0.0, 10.0, 0).is_empty());
+    }
+
+    #[test]
+    fn log_ticks_on_decades() {
+        assert_eq!(
+            Graph2DWidget::axis_ticks(AxisScale::Log, 1.0, 1000.0, 5),
+            vec![1.0, 10.0, 100.0, 1000.0]
+        );
+        assert_eq!(
+            Graph2DWidget::axis_ticks(AxisScale::Log, 1.0, 1e6, 2),
+            vec![1.0, 1e2, 1e4, 1e6]
+        );
+    }
+
+    #[test]
+    fn log_ticks_within_a_decade() {
+        let ticks = Graph2DWidget::axis_ticks(AxisScale::Log, 1.0, 10.0 - 1e-9, 1);
+
+        assert_eq!(ticks.len(), 1);
+        assert!(f64::abs(ticks[0] - f64::sqrt(10.0)) < 1e-6);
+    }
+
+    #[test]
+    fn log_ticks_of_non_positive_range() {
+        assert!(Graph2DWidget::axis_ticks(AxisScale::Log, -1.0, 10.0, 4).is_empty());
+        assert!(Graph2DWidget::axis_ticks(AxisScale::Log, 0.0, 10.0, 4).is_empty());
+    }
+
+    #[test]
+    fn axis_scale_round_trip() {
+        for v in [0.001, 1.0, 42.0, 1e6] {
+            assert!(close(
+                AxisScale::Log.inverse(AxisScale::Log.forward(v)) / v,
+                1.0
+            ));
+            assert_eq!(AxisScale::Linear.inverse(AxisScale::Linear.forward(v)), v);
+        }
+
+        assert_eq!(AxisScale::Log.forward(100.0), 2.0);
+    }
+
+    #[test]
+    fn margins_make_room_for_the_labels() {
+        let bare = Graph2DWidget::margins(14, 12, 30, false, false, false);
+        let labeled = Graph2DWidget::margins(14, 12, 30, true, true, true);
+
+        assert_eq!(bare, (38, 8, 12, 20));
+        assert_eq!(labeled, (38 + 18, 8 + 14, 12, 20 + 18));
+    }
+
+    #[test]
+    fn margins_follow_the_tick_labels() {
+        let narrow = Graph2DWidget::margins(14, 12, 20, false, false, false);
+        let wide = Graph2DWidget::margins(14, 12, 50, false, false, false);
+
+        assert_eq!(wide.0 - narrow.0, 30);
+        assert_eq!((wide.1, wide.2, wide.3), (narrow.1, narrow.2, narrow.3));
+    }
+
+    #[test]
+    fn plot_area_uses_the_cached_margins() {
+        on_ui_thread(|| {
+            let mut graph = Graph2DWidget::new(10, 20, 300, 200, "");
+            *graph.margins.borrow_mut() = (40, 10, 12, 30);
+
+            assert_eq!(graph.plot_area(), (50, 30, 248, 160));
+
+            // A resize moves the area without measuring anything.
+            graph.widget.widget.resize(0, 0, 200, 100);
+            assert_eq!(graph.plot_area(), (40, 10, 148, 60));
+        });
+    }
+
+    #[test]
+    fn plot_area_never_collapses() {
+        on_ui_thread(|| {
+            let graph = Graph2DWidget::new(0, 0, 30, 20, "");
+            *graph.margins.borrow_mut() = (40, 10, 12, 30);
+
+            assert_eq!(graph.plot_area(), (40, 10, 1, 1));
+        });
+    }
+
+    #[test]
+    fn moved_points_keep_x_when_it_is_locked() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.edit.borrow_mut().lock_x = true;
+
+            graph.move_point(0, 1, 80, 20);
+
+            let value = graph.data.borrow()[0].as_ref().unwrap().get_value(1);
+            assert_eq!(value, Some((5.0, 8.0)));
+        });
+    }
+
+    #[test]
+    fn moved_points_keep_a_sorted_series_sorted() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.edit.borrow_mut().monotonic_x = true;
+
+            graph.move_point(0, 1, 150, 50);
+
+            let d = graph.data.borrow()[0].clone().unwrap();
+            assert_eq!(d.get_value(1), Some((10.0, 5.0)));
+            assert_eq!((d.x_max, d.y_max), (10.0, 10.0));
+        });
+    }
+
+    #[test]
+    fn deleting_a_point_removes_its_data_tips() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.add_data_tip(0, 1);
+            graph.add_data_tip(0, 2);
+
+            assert_eq!(graph.delete_point(0, 1), Some((5.0, 5.0)));
+            assert_eq!(graph.data_tips.borrow().len(), 1);
+            assert_eq!(graph.data_tips.borrow()[0].x, 10.0);
+        });
+    }
+
+    #[test]
+    fn tips_without_formatter_show_the_coordinates() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let tip = graph.add_data_tip(0, 1).unwrap();
+            let tip = graph.data_tips.borrow()[tip];
+
+            assert_eq!(graph.tip_text(&tip), "x: 5.00 y: 5.00");
+        });
+    }
+
+    #[test]
+    fn tip_formatters_get_the_sample_and_its_metadata() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            if let Some(d) = graph.data.borrow_mut()[0].as_mut() {
+                d.name = "ramp".to_string();
+                d.metadata = ["a", "b", "c"].map(String::from).to_vec();
+            }
+            graph.set_tip_formatter(
+                0,
+                Some(Rc::new(|info: &TipInfo| {
+                    let metadata = info.metadata.unwrap_or("-");
+                    format!(
+                        "{} {} {} {}",
+                        info.name, info.index, metadata, info.interpolated
+                    )
+                })),
+            );
+
+            let tip = graph.add_data_tip(0, 2).unwrap();
+            let tip = graph.data_tips.borrow()[tip];
+            assert_eq!(graph.tip_text(&tip), "ramp 2 c false");
+
+            // Between samples, the formatter is told the nearest one.
+            let tip = graph.add_data_tip_at(0, 4.0, 4.0).unwrap();
+            let tip = graph.data_tips.borrow()[tip];
+            assert_eq!(graph.tip_text(&tip), "ramp 1 b true");
+
+            graph.set_tip_formatter(0, None);
+            assert_eq!(graph.tip_text(&tip), "x: 4.00 y: 4.00");
+        });
+    }
+
+    #[test]
+    fn tips_snap_to_samples_or_onto_the_line() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            assert_eq!(graph.tip_position(None, 48, 52, 10.0), Some((0, 5.0, 5.0)));
+            assert_eq!(graph.tip_position(None, 5, 95, 10.0), Some((0, 0.0, 0.0)));
+            assert_eq!(graph.tip_position(None, 20, 80, 10.0), None);
+
+            graph.set_tip_snap(TipSnap::Interpolate);
+            let (series, x, y) = graph.tip_position(None, 20, 80, 10.0).unwrap();
+            assert_eq!(series, 0);
+            assert!(close(x, 2.0) && close(y, 2.0));
+
+            assert_eq!(graph.tip_position(None, 20, 20, 10.0), None);
+        });
+    }
+
+    #[test]
+    fn zoom_along_one_axis_keeps_the_other() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            graph.zoom_at(50, 50, 0.2, ZoomAxis::X);
+            assert_eq!(*graph.limit_c.borrow(), limit(1.0, 9.0, 0.0, 10.0));
+
+            graph.zoom_at(50, 50, 0.2, ZoomAxis::Y);
+            assert_eq!(*graph.limit_c.borrow(), limit(1.0, 9.0, 1.0, 9.0));
+        });
+    }
+
+    #[test]
+    fn pan_along_one_axis_keeps_the_other() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            graph.start_pan(50, 50, ZoomAxis::Y);
+            graph.pan_to(70, 30);
+
+            assert_eq!(*graph.limit_c.borrow(), limit(0.0, 10.0, -2.0, 8.0));
+        });
+    }
+
+    #[test]
+    fn tick_labels_select_the_axis() {
+        on_ui_thread(|| {
+            let graph = graph();
+            *graph.margins.borrow_mut() = (20, 0, 0, 20);
+
+            assert_eq!(graph.axis_region(50, 90), Some(ZoomAxis::X));
+            assert_eq!(graph.axis_region(10, 40), Some(ZoomAxis::Y));
+            assert_eq!(graph.axis_region(50, 40), None);
+            assert_eq!(graph.axis_region(10, 90), None);
+        });
+    }
+
+    #[test]
+    fn linked_graphs_follow_zoom_and_pan() {
+        on_ui_thread(|| {
+            let group = AxisLinkGroup::new(LinkAxis::X);
+            let mut a = graph();
+            let mut b = graph();
+            *b.limit_c.borrow_mut() = limit(20.0, 30.0, 20.0, 30.0);
+
+            a.link_axes(&group);
+            b.link_axes(&group);
+            assert_eq!(*b.limit_c.borrow(), limit(0.0, 10.0, 20.0, 30.0));
+
+            a.pan_view(0.5, 0.5);
+            assert_eq!(*b.limit_c.borrow(), limit(5.0, 15.0, 20.0, 30.0));
+
+            b.zoom_at(50, 50, 0.2, ZoomAxis::Both);
+            assert_eq!(*a.limit_c.borrow(), limit(6.0, 14.0, 5.0, 15.0));
+        });
+    }
+
+    #[test]
+    fn hidden_graphs_leave_their_link_groups() {
+        on_ui_thread(|| {
+            let group = AxisLinkGroup::new(LinkAxis::XY);
+            let mut a = graph();
+            let mut b = graph();
+            a.link_axes(&group);
+            b.link_axes(&group);
+
+            // The graph is hidden along with its window, it is kept alive by its handler.
+            b.widget.widget.handle_event(Event::Hide);
+            a.pan_view(0.5, 0.0);
+            assert_eq!(*b.limit_c.borrow(), limit(0.0, 10.0, 0.0, 10.0));
+            assert_eq!(group.members.borrow().len(), 1);
+
+            // Shown again, it catches up with the group.
+            b.widget.widget.handle_event(Event::Show);
+            assert_eq!(*b.limit_c.borrow(), limit(5.0, 15.0, 0.0, 10.0));
+            a.pan_view(0.0, 0.5);
+            assert_eq!(*b.limit_c.borrow(), limit(5.0, 15.0, 5.0, 15.0));
+        });
+    }
+
+    #[test]
+    fn measure_cursors_read_the_visible_series() {
+        on_ui_thread(|| {
+            let mut graph = graph_with_hidden_series();
+            assert!(graph.measure_values().is_empty());
+
+            graph.set_measure_cursors(Some(MeasureCursors {
+                x1: 2.5,
+                x2: 12.0,
+                y1: 0.0,
+                y2: 0.0,
+                horizontal: false,
+            }));
+            assert_eq!(graph.measure_values(), vec![(0, Some(2.5), None)]);
+
+            if let Some(d) = graph.data.borrow_mut()[1].as_mut() {
+                d.visible = true;
+            }
+            assert_eq!(
+                graph.measure_values(),
+                vec![(0, Some(2.5), None), (1, Some(7.5), None)]
+            );
+        });
+    }
+
+    #[test]
+    fn measure_cursors_start_inside_the_view() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.show_measure_cursors(true);
+
+            let c = graph.measure_cursors.borrow().unwrap();
+            assert!(close(c.x1, 10.0 / 3.0) && close(c.x2, 20.0 / 3.0));
+            assert!(close(c.y1, 10.0 / 3.0) && close(c.y2, 20.0 / 3.0));
+            assert!(c.horizontal);
+        });
+    }
+
+    #[test]
+    fn readouts_name_series_by_name_or_number() {
+        on_ui_thread(|| {
+            let graph = graph_with_hidden_series();
+            if let Some(d) = graph.data.borrow_mut()[1].as_mut() {
+                d.name = "falling".to_string();
+            }
+
+            assert_eq!(graph.series_label(0), "Series 1");
+            assert_eq!(graph.series_label(1), "falling");
+        });
+    }
+
+    #[test]
+    fn region_statistics_cover_the_visible_series() {
+        on_ui_thread(|| {
+            let mut graph = graph_with_hidden_series();
+            assert!(graph.region_statistics().is_empty());
+
+            // The region is kept with its bounds in order.
+            graph.set_stats_region(Some((10.0, 0.0)));
+            assert_eq!(*graph.stats_region.borrow(), Some((0.0, 10.0)));
+
+            let stats = graph.region_statistics();
+            assert_eq!(stats.len(), 1);
+            let (series, s) = stats[0];
+            let s = s.unwrap();
+            assert_eq!((series, s.count, s.min, s.max), (0, 3, 0.0, 10.0));
+            assert!(close(s.mean, 5.0));
+        });
+    }
+
+    #[test]
+    fn region_statistics_report_series_without_samples_in_the_region() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.set_stats_region(Some((6.0, 9.0)));
+
+            assert_eq!(graph.region_statistics(), vec![(0, None)]);
+        });
+    }
+
+    #[test]
+    fn history_steps_back_and_forward() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let home = *graph.limit_c.borrow();
+            assert!(!graph.view_back());
+
+            graph.zoom_at(50, 50, 0.2, ZoomAxis::Both);
+            graph.record_view(home);
+            let zoomed = *graph.limit_c.borrow();
+            graph.pan_view(0.5, 0.0);
+            graph.record_view(zoomed);
+            let panned = *graph.limit_c.borrow();
+
+            assert!(graph.view_back());
+            assert_eq!(*graph.limit_c.borrow(), zoomed);
+            assert!(graph.view_back());
+            assert_eq!(*graph.limit_c.borrow(), home);
+            assert!(!graph.view_back());
+            assert!(graph.view_forward());
+            assert!(graph.view_forward());
+            assert_eq!(*graph.limit_c.borrow(), panned);
+            assert!(!graph.view_forward());
+        });
+    }
+
+    #[test]
+    fn new_views_drop_the_forward_history() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let home = *graph.limit_c.borrow();
+            graph.pan_view(0.5, 0.0);
+            graph.record_view(home);
+            assert!(graph.view_back());
+
+            graph.pan_view(0.0, 0.5);
+            graph.record_view(home);
+
+            assert_eq!(graph.history.borrow().len(), 2);
+            assert!(!graph.view_forward());
+        });
+    }
+
+    #[test]
+    fn unchanged_views_are_not_recorded() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let events = record_events(&mut graph);
+            let home = *graph.limit_c.borrow();
+
+            graph.record_view(home);
+
+            assert!(graph.history.borrow().is_empty());
+            assert!(events.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn wheel_steps_are_reported_each_but_recorded_once() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let events = record_events(&mut graph);
+            let home = *graph.limit_c.borrow();
+
+            for _ in 0..3 {
+                let before = *graph.limit_c.borrow();
+                graph.zoom_at(50, 50, 0.1, ZoomAxis::Both);
+                graph.wheel_step(before);
+            }
+            assert_eq!(events.borrow().len(), 3);
+            assert!(graph.wheel_timer.borrow().is_some());
+
+            graph.finish_wheel();
+            assert!(graph.wheel_timer.borrow().is_none());
+            assert_eq!(*graph.history.borrow(), vec![home, *graph.limit_c.borrow()]);
+            assert_eq!(events.borrow().len(), 3);
+
+            assert!(graph.view_back());
+            assert_eq!(*graph.limit_c.borrow(), home);
+        });
+    }
+
+    #[test]
+    fn other_view_changes_end_a_wheel_gesture() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let home = *graph.limit_c.borrow();
+
+            graph.zoom_at(50, 50, 0.1, ZoomAxis::Both);
+            graph.wheel_step(home);
+            let zoomed = *graph.limit_c.borrow();
+
+            graph.pan_view(0.5, 0.0);
+            graph.record_view(zoomed);
+
+            let panned = *graph.limit_c.borrow();
+            assert_eq!(*graph.history.borrow(), vec![home, zoomed, panned]);
+            assert!(graph.wheel_start.borrow().is_none());
+        });
+    }
+
+    #[test]
+    fn horizontal_scrolling_pans_along_x() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            graph.handle_wheel(50, 50, 2.0, 0.0, false, false);
+
+            assert_eq!(*graph.limit_c.borrow(), limit(1.0, 11.0, 0.0, 10.0));
+        });
+    }
+
+    #[test]
+    fn shift_wheel_zooms_along_x() {
+        on_ui_thread(|| {
+            // Whether the platform reports Shift+wheel as a vertical or horizontal scroll.
+            for (dx, dy) in [(0.0, 1.0), (1.0, 0.0)] {
+                let mut graph = graph();
+
+                graph.handle_wheel(50, 50, dx, dy, true, false);
+
+                assert_eq!(*graph.limit_c.borrow(), limit(0.5, 9.5, 0.0, 10.0));
+            }
+        });
+    }
+
+    #[test]
+    fn fast_trackpad_scrolls_zoom_a_limited_step() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            graph.handle_wheel(50, 50, 0.0, 20.0, false, false);
+
+            assert_eq!(*graph.limit_c.borrow(), limit(2.5, 7.5, 2.5, 7.5));
+        });
+    }
+
+    #[test]
+    fn wheel_pans_when_configured_to() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.wheel.borrow_mut().action = WheelAction::Pan;
+
+            graph.handle_wheel(50, 50, 0.0, 2.0, false, false);
+            assert_eq!(*graph.limit_c.borrow(), limit(0.0, 10.0, -1.0, 9.0));
+
+            // Ctrl still zooms.
+            graph.handle_wheel(50, 50, 0.0, 1.0, false, true);
+            assert_eq!(*graph.limit_c.borrow(), limit(0.5, 9.5, -0.5, 8.5));
+        });
+    }
+
+    #[test]
+    fn tab_is_left_alone_without_data_tips() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            assert!(!graph.handle_key(Key::Tab, "\t", false, false));
+        });
+    }
+
+    #[test]
+    fn tab_cycles_through_the_data_tips() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.add_data_tip(0, 0);
+            graph.add_data_tip(0, 2);
+            *graph.selected_tip.borrow_mut() = None;
+
+            assert!(graph.handle_key(Key::Tab, "\t", false, false));
+            assert_eq!(*graph.selected_tip.borrow(), Some(0));
+            assert!(graph.handle_key(Key::Tab, "\t", false, false));
+            assert_eq!(*graph.selected_tip.borrow(), Some(1));
+            assert!(graph.handle_key(Key::Tab, "\t", false, true));
+            assert_eq!(*graph.selected_tip.borrow(), Some(0));
+        });
+    }
+
+    #[test]
+    fn arrows_step_the_selected_tip_or_pan() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            assert!(graph.handle_key(Key::Right, "", false, false));
+            assert_eq!(*graph.limit_c.borrow(), limit(1.0, 11.0, 0.0, 10.0));
+
+            graph.add_data_tip(0, 0);
+            assert!(graph.handle_key(Key::Right, "", false, false));
+            assert_eq!(graph.data_tips.borrow()[0].x, 5.0);
+            assert_eq!(*graph.limit_c.borrow(), limit(1.0, 11.0, 0.0, 10.0));
+
+            assert!(graph.handle_key(Key::Delete, "", false, false));
+            assert!(graph.data_tips.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn alt_arrows_step_through_the_history() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            assert!(!graph.handle_key(Key::Left, "", true, false));
+
+            assert!(graph.handle_key(Key::from_char('+'), "+", false, false));
+            assert!(graph.handle_key(Key::Left, "", true, false));
+            assert_eq!(*graph.limit_c.borrow(), limit(0.0, 10.0, 0.0, 10.0));
+            assert!(graph.handle_key(Key::Right, "", true, false));
+            assert_eq!(*graph.limit_c.borrow(), limit(1.0, 9.0, 1.0, 9.0));
+        });
+    }
+
+    #[test]
+    fn unused_keys_are_left_to_fltk() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+
+            assert!(!graph.handle_key(Key::Escape, "", false, false));
+            assert!(!graph.handle_key(Key::from_char('q'), "q", false, false));
+            assert!(graph.history.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn tool_clicks_follow_the_tool_binding() {
+        use MouseAction::*;
+
+        assert!(Graph2DWidget::is_tool_click(Tool, Tool));
+        // The tool moved to the right button, which was bound to pan before.
+        assert!(Graph2DWidget::is_tool_click(Tool, None));
+        assert!(Graph2DWidget::is_tool_click(Pan, Tool));
+        assert!(!Graph2DWidget::is_tool_click(Tool, ContextMenu));
+        assert!(!Graph2DWidget::is_tool_click(Pan, None));
+    }
+
+    #[test]
+    fn clicks_near_a_sample_report_it() {
+        on_ui_thread(|| {
+            let mut graph = graph_with_hidden_series();
+            let events = record_events(&mut graph);
+
+            graph.click_point(52, 48);
+            // Hidden series and points off the samples are not reported.
+            graph.click_point(2, 2);
+            graph.click_point(30, 70);
+
+            assert_eq!(
+                *events.borrow(),
+                vec![PlotEvent::PointClicked {
+                    series: 0,
+                    index: 1,
+                    x: 5.0,
+                    y: 5.0
+                }]
+            );
+        });
+    }
+
+    #[test]
+    fn dragged_regions_are_reported_in_order() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let events = record_events(&mut graph);
+
+            graph.start_selection(70, 50);
+            graph.drag_selection(20, 50);
+            graph.finish_selection();
+
+            let (x_left, x_right) = graph.stats_region.borrow().unwrap();
+            assert!(close(x_left, 2.0) && close(x_right, 7.0));
+            assert_eq!(
+                *events.borrow(),
+                vec![PlotEvent::SelectionMade { x_left, x_right }]
+            );
+            assert!(!*graph.selecting_region.borrow());
+        });
+    }
+
+    #[test]
+    fn clicks_without_a_drag_clear_the_region() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            graph.set_stats_region(Some((2.0, 7.0)));
+            let events = record_events(&mut graph);
+
+            graph.start_selection(50, 50);
+            graph.drag_selection(51, 50);
+            graph.finish_selection();
+
+            assert_eq!(*graph.stats_region.borrow(), None);
+            assert!(events.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn data_tip_changes_are_reported() {
+        on_ui_thread(|| {
+            let mut graph = graph();
+            let events = record_events(&mut graph);
+
+            graph.add_data_tip(0, 1);
+            graph.move_data_tip(0, 10.0, 10.0);
+            graph.move_data_tip(0, 10.0, 10.0);
+            graph.remove_data_tip(0);
+            graph.remove_data_tip(0);
+
+            let events = events.borrow();
+            assert_eq!(events.len(), 3);
+            assert!(matches!(
+                events[0],
+                PlotEvent::DataTipAdded { index: 0, tip } if tip.x == 5.0 && tip.y == 5.0
+            ));
+            assert!(matches!(
+                events[1],
+                PlotEvent::DataTipMoved { index: 0, tip } if tip.x == 10.0
+            ));
+            assert!(matches!(
+                events[2],
+                PlotEvent::DataTipRemoved { index: 0, tip } if tip.x == 10.0
+            ));
+        });
     }
 }
@@ -2,9 +2,9 @@ use std::cell::RefCell;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 
-use fltk::{prelude::*, enums::Color, app::*, draw::*, widget::*};
+use fltk::{prelude::*, enums::Color, app::redraw, draw::*, widget::*};
 
-use crate::widgets::widget::*;
+use crate::widgets::{graph_2d_widget::Graph2DWidget, widget::*};
 
 #[derive(Clone, Debug)]
 pub struct GraphWidget {
@@ -78,6 +78,10 @@ impl MyWidget for GraphWidget {
     fn set_hold(&mut self, hold: bool) {
         *self.hold.borrow_mut() = hold;
     }
+
+    fn graph_2d(&self) -> Option<Graph2DWidget> {
+        None
+    }
 }
 
 impl Deref for GraphWidget {
@@ -0,0 +1,228 @@
+use fltk::{app::MouseButton, enums::Shortcut};
+
+// What dragging with the left mouse button does in a plot. The other buttons follow the
+// mouse bindings in every mode.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum InteractionMode {
+    // Places and moves data tips, or draws a zoom box when not clicking near a point.
+    #[default]
+    Auto,
+    ZoomBox,
+    ZoomX,
+    ZoomY,
+    Pan,
+    DataCursor,
+    EditData,
+    Measure,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum WheelAction {
+    #[default]
+    Zoom,
+    Pan,
+}
+
+// How the plots react to the mouse wheel and to scrolling on a trackpad. The steps are
+// fractions of the view per wheel notch; trackpads that report larger deltas move further.
+// Horizontal scrolling always pans along x. With the Pan action, Ctrl+wheel zooms.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WheelConfig {
+    pub action: WheelAction,
+    pub zoom_step: f64,
+    pub pan_step: f64,
+}
+
+impl Default for WheelConfig {
+    fn default() -> Self {
+        WheelConfig {
+            action: WheelAction::Zoom,
+            zoom_step: 0.1,
+            pan_step: 0.05,
+        }
+    }
+}
+
+// Constraints on dragging points in the EditData mode. The snap steps are in data units,
+// points snap to multiples of them and a step of zero leaves that axis free.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct EditConfig {
+    // Only y can be changed, x keeps the value of the sample.
+    pub lock_x: bool,
+    pub snap: Option<(f64, f64)>,
+    // Keeps x between the x of the neighbouring samples, so a sorted series stays sorted.
+    pub monotonic_x: bool,
+}
+
+// The axes that a zoom or pan changes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ZoomAxis {
+    #[default]
+    Both,
+    X,
+    Y,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Gesture {
+    // The button is pressed and released without moving the mouse.
+    Click,
+    // The action starts when the button is pressed and follows the mouse until released.
+    Drag,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MouseAction {
+    // Whatever the interaction mode does: data tips, zoom box, pan, editing data or measuring.
+    Tool,
+    ZoomBox,
+    // Pans the view, or moves the data tip label or annotation under the mouse pointer.
+    Pan,
+    // Adds a data tip to the point under the mouse pointer, or moves an existing one.
+    DataTip,
+    // Removes the data tip under the mouse pointer, or resets the view.
+    Reset,
+    // Opens the context menu of the subplot at the mouse pointer.
+    ContextMenu,
+    None,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MouseBinding {
+    pub button: MouseButton,
+    pub modifiers: Shortcut,
+    pub gesture: Gesture,
+    pub action: MouseAction,
+}
+
+impl MouseBinding {
+    pub fn new(button: MouseButton, gesture: Gesture, action: MouseAction) -> MouseBinding {
+        MouseBinding {
+            button,
+            modifiers: Shortcut::None,
+            gesture,
+            action,
+        }
+    }
+
+    pub fn with_modifiers(mut self, modifiers: Shortcut) -> MouseBinding {
+        self.modifiers = modifiers;
+        self
+    }
+
+    // Left drag uses the interaction mode, middle drag pans and right click opens the
+    // context menu.
+    pub fn defaults() -> Vec<MouseBinding> {
+        vec![
+            MouseBinding::new(MouseButton::Left, Gesture::Drag, MouseAction::Tool),
+            MouseBinding::new(MouseButton::Middle, Gesture::Drag, MouseAction::Pan),
+            MouseBinding::new(MouseButton::Right, Gesture::Click, MouseAction::ContextMenu),
+        ]
+    }
+
+    // Returns the action bound to the button and gesture with the given modifiers held. A
+    // binding without modifiers applies if no binding matches the modifiers exactly, so
+    // Shift and Ctrl still reach the zoom box of the left button.
+    pub fn find(
+        bindings: &[MouseBinding],
+        button: MouseButton,
+        modifiers: Shortcut,
+        gesture: Gesture,
+    ) -> MouseAction {
+        let matches = |m: Shortcut| {
+            bindings
+                .iter()
+                .find(|b| b.button == button && b.gesture == gesture && b.modifiers == m)
+                .map(|b| b.action)
+        };
+
+        matches(modifiers)
+            .or_else(|| matches(Shortcut::None))
+            .unwrap_or(MouseAction::None)
+    }
+}
+
+// How a data tip is placed on its series when it is added or dragged.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum TipSnap {
+    // On the sample closest to the mouse pointer.
+    #[default]
+    Nearest,
+    // On the point of the line between two samples that is closest to the mouse pointer.
+    Interpolate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_map_buttons_to_actions() {
+        let bindings = MouseBinding::defaults();
+        let find = |button, gesture| MouseBinding::find(&bindings, button, Shortcut::None, gesture);
+
+        assert_eq!(find(MouseButton::Left, Gesture::Drag), MouseAction::Tool);
+        assert_eq!(find(MouseButton::Middle, Gesture::Drag), MouseAction::Pan);
+        assert_eq!(
+            find(MouseButton::Right, Gesture::Click),
+            MouseAction::ContextMenu
+        );
+        assert_eq!(find(MouseButton::Right, Gesture::Drag), MouseAction::None);
+    }
+
+    #[test]
+    fn modifiers_fall_back_to_the_plain_binding() {
+        let bindings = MouseBinding::defaults();
+
+        // Shift and Ctrl constrain the zoom box to one axis, so they must still reach it.
+        for modifiers in [Shortcut::Shift, Shortcut::Ctrl] {
+            assert_eq!(
+                MouseBinding::find(&bindings, MouseButton::Left, modifiers, Gesture::Drag),
+                MouseAction::Tool
+            );
+        }
+    }
+
+    #[test]
+    fn exact_modifiers_take_precedence() {
+        let mut bindings = MouseBinding::defaults();
+        bindings.push(
+            MouseBinding::new(MouseButton::Left, Gesture::Drag, MouseAction::Pan)
+                .with_modifiers(Shortcut::Alt),
+        );
+
+        assert_eq!(
+            MouseBinding::find(&bindings, MouseButton::Left, Shortcut::Alt, Gesture::Drag),
+            MouseAction::Pan
+        );
+        assert_eq!(
+            MouseBinding::find(&bindings, MouseButton::Left, Shortcut::None, Gesture::Drag),
+            MouseAction::Tool
+        );
+    }
+
+    #[test]
+    fn no_bindings_do_nothing() {
+        assert_eq!(
+            MouseBinding::find(&[], MouseButton::Left, Shortcut::None, Gesture::Click),
+            MouseAction::None
+        );
+    }
+
+    #[test]
+    fn tool_can_move_to_another_button() {
+        let bindings = [
+            MouseBinding::new(MouseButton::Right, Gesture::Drag, MouseAction::Tool),
+            MouseBinding::new(MouseButton::Left, Gesture::Click, MouseAction::ContextMenu),
+        ];
+
+        assert_eq!(
+            MouseBinding::find(&bindings, MouseButton::Right, Shortcut::None, Gesture::Drag),
+            MouseAction::Tool
+        );
+        assert_eq!(
+            MouseBinding::find(&bindings, MouseButton::Left, Shortcut::None, Gesture::Drag),
+            MouseAction::None
+        );
+    }
+}
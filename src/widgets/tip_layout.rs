@@ -0,0 +1,243 @@
+// Placement of the data tip labels. Rectangles are (x, y, w, h) in pixels, like the plot area.
+pub type Rect = (i32, i32, i32, i32);
+
+// Gap between a marker and the label placed next to it.
+const GAP: i32 = 8;
+const CELL: i32 = 4;
+
+// A coarse grid over the plot area that marks the cells covered by data, markers and the
+// labels that were placed already.
+#[derive(Clone, Debug)]
+pub struct Occupancy {
+    area: Rect,
+    cols: i32,
+    rows: i32,
+    cells: Vec<bool>,
+}
+
+#[allow(dead_code)]
+impl Occupancy {
+    pub fn new(area: Rect) -> Occupancy {
+        let cols = i32::max(area.2 / CELL + 1, 1);
+        let rows = i32::max(area.3 / CELL + 1, 1);
+
+        Occupancy {
+            area,
+            cols,
+            rows,
+            cells: vec![false; (cols * rows) as usize],
+        }
+    }
+
+    fn cell(&self, px: i32, py: i32) -> Option<usize> {
+        let c = (px - self.area.0).div_euclid(CELL);
+        let r = (py - self.area.1).div_euclid(CELL);
+
+        if c >= 0 && c < self.cols && r >= 0 && r < self.rows {
+            Some((r * self.cols + c) as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn mark_point(&mut self, px: f64, py: f64) {
+        if px.is_finite() && py.is_finite() {
+            if let Some(i) = self.cell(px as i32, py as i32) {
+                self.cells[i] = true;
+            }
+        }
+    }
+
+    // Marks the cells along a line, only the part inside the plot area is walked.
+    pub fn mark_line(&mut self, p0: (f64, f64), p1: (f64, f64)) {
+        let (x, y, w, h) = self.area;
+        let (x, y, w, h) = (x as f64, y as f64, w as f64, h as f64);
+
+        if ![p0.0, p0.1, p1.0, p1.1].iter().all(|v| v.is_finite())
+            || f64::max(p0.0, p1.0) < x
+            || f64::min(p0.0, p1.0) > x + w
+            || f64::max(p0.1, p1.1) < y
+            || f64::min(p0.1, p1.1) > y + h
+        {
+            return;
+        }
+
+        let len = f64::max(f64::abs(p1.0 - p0.0), f64::abs(p1.1 - p0.1));
+        let steps = (len / CELL as f64)
+            .ceil()
+            .min(f64::from(self.cols + self.rows) * 4.0);
+        let steps = f64::max(steps, 1.0) as i32;
+
+        for k in 0..=steps {
+            let t = k as f64 / steps as f64;
+            self.mark_point(p0.0 + t * (p1.0 - p0.0), p0.1 + t * (p1.1 - p0.1));
+        }
+    }
+
+    pub fn mark_rect(&mut self, rect: Rect) {
+        let cells: Vec<usize> = self.cells_of(rect).collect();
+        for i in cells {
+            self.cells[i] = true;
+        }
+    }
+
+    // Number of covered cells under a rectangle.
+    pub fn count(&self, rect: Rect) -> usize {
+        self.cells_of(rect).filter(|i| self.cells[*i]).count()
+    }
+
+    fn cells_of(&self, rect: Rect) -> impl Iterator<Item = usize> + '_ {
+        let (x, y, w, h) = rect;
+        let c0 = i32::max((x - self.area.0).div_euclid(CELL), 0);
+        let c1 = i32::min((x + w - self.area.0).div_euclid(CELL), self.cols - 1);
+        let r0 = i32::max((y - self.area.1).div_euclid(CELL), 0);
+        let r1 = i32::min((y + h - self.area.1).div_euclid(CELL), self.rows - 1);
+
+        (r0..=r1).flat_map(move |r| (c0..=c1).map(move |c| (r * self.cols + c) as usize))
+    }
+}
+
+// Moves a rectangle inside the area, or to its top left corner if it is too large.
+pub fn clamp_rect(rect: Rect, area: Rect) -> Rect {
+    let (x, y, w, h) = rect;
+    let x = x.min(area.0 + area.2 - w).max(area.0);
+    let y = y.min(area.1 + area.3 - h).max(area.1);
+
+    (x, y, w, h)
+}
+
+// Finds a place for a label of size (w, h) around a marker, trying the positions closest to
+// the marker first. The first place inside the area that covers nothing is taken, otherwise
+// the one that covers the least.
+pub fn place_label(
+    marker: (i32, i32),
+    radius: i32,
+    size: (i32, i32),
+    occupancy: &Occupancy,
+) -> Rect {
+    let (px, py) = marker;
+    let (w, h) = size;
+    let mut best = None;
+
+    for distance in [GAP, 3 * GAP, 6 * GAP] {
+        let d = radius + distance;
+        let candidates = [
+            (px + d, py - d - h),
+            (px - d - w, py - d - h),
+            (px + d, py + d),
+            (px - d - w, py + d),
+            (px + d, py - h / 2),
+            (px - d - w, py - h / 2),
+            (px - w / 2, py - d - h),
+            (px - w / 2, py + d),
+        ];
+
+        for (x, y) in candidates {
+            let rect = clamp_rect((x, y, w, h), occupancy.area);
+            let covered = occupancy.count(rect);
+
+            if covered == 0 && rect.0 == x && rect.1 == y {
+                return rect;
+            }
+
+            // Positions that had to be moved into the area are only taken if nothing else fits.
+            let score = (rect.0 != x || rect.1 != y, covered);
+            if best.is_none_or(|(s, _)| score < s) {
+                best = Some((score, rect));
+            }
+        }
+    }
+
+    best.map(|(_, rect)| rect).unwrap_or((px, py, w, h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = (0, 0, 200, 100);
+
+    fn overlaps(a: Rect, b: Rect) -> bool {
+        a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+    }
+
+    fn inside(rect: Rect, area: Rect) -> bool {
+        rect.0 >= area.0
+            && rect.1 >= area.1
+            && rect.0 + rect.2 <= area.0 + area.2
+            && rect.1 + rect.3 <= area.1 + area.3
+    }
+
+    #[test]
+    fn clamp_keeps_rects_that_fit() {
+        assert_eq!(clamp_rect((10, 10, 20, 20), AREA), (10, 10, 20, 20));
+    }
+
+    #[test]
+    fn clamp_moves_rects_inside() {
+        assert_eq!(clamp_rect((190, -5, 20, 20), AREA), (180, 0, 20, 20));
+        assert_eq!(clamp_rect((-30, 95, 20, 20), AREA), (0, 80, 20, 20));
+    }
+
+    #[test]
+    fn clamp_puts_large_rects_at_the_top_left() {
+        assert_eq!(clamp_rect((50, 50, 300, 150), AREA), (0, 0, 300, 150));
+    }
+
+    #[test]
+    fn label_goes_top_right_in_an_empty_plot() {
+        let occupancy = Occupancy::new(AREA);
+        let rect = place_label((100, 50), 5, (40, 14), &occupancy);
+
+        assert_eq!(rect, (100 + 5 + GAP, 50 - 5 - GAP - 14, 40, 14));
+    }
+
+    #[test]
+    fn label_stays_inside_near_the_border() {
+        let occupancy = Occupancy::new(AREA);
+        let rect = place_label((195, 3), 5, (40, 14), &occupancy);
+
+        assert!(inside(rect, AREA));
+        assert!(!overlaps(rect, (190, -2, 10, 10)));
+    }
+
+    #[test]
+    fn labels_avoid_each_other() {
+        let mut occupancy = Occupancy::new(AREA);
+        let first = place_label((100, 50), 5, (40, 14), &occupancy);
+        occupancy.mark_rect(first);
+        let second = place_label((100, 50), 5, (40, 14), &occupancy);
+
+        assert!(!overlaps(first, second));
+        assert!(inside(second, AREA));
+    }
+
+    #[test]
+    fn labels_avoid_data() {
+        let mut occupancy = Occupancy::new(AREA);
+        occupancy.mark_line((100.0, 50.0), (200.0, 0.0));
+        let rect = place_label((100, 50), 5, (40, 14), &occupancy);
+
+        assert_eq!(occupancy.count(rect), 0);
+    }
+
+    #[test]
+    fn occupancy_ignores_points_outside_and_non_finite() {
+        let mut occupancy = Occupancy::new(AREA);
+        occupancy.mark_point(-10.0, 50.0);
+        occupancy.mark_point(f64::NAN, 50.0);
+        occupancy.mark_line((f64::INFINITY, 0.0), (10.0, 10.0));
+        occupancy.mark_line((-50.0, -50.0), (-10.0, -10.0));
+
+        assert_eq!(occupancy.count(AREA), 0);
+    }
+
+    #[test]
+    fn occupancy_counts_marked_cells() {
+        let mut occupancy = Occupancy::new(AREA);
+        occupancy.mark_point(10.0, 10.0);
+
+        assert_eq!(occupancy.count((8, 8, 4, 4)), 1);
+        assert_eq!(occupancy.count((50, 50, 10, 10)), 0);
+    }
+}
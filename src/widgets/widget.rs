@@ -1,5 +1,7 @@
 use fltk::{enums::Color, draw::*};
 
+use crate::widgets::graph_2d_widget::Graph2DWidget;
+
 pub trait MyWidget {
     fn put_data(&mut self, x: &[f64], y: &[f64], style: LineStyle, width: i32, color: Color);
     fn handle(&mut self);
@@ -8,6 +10,7 @@ pub trait MyWidget {
     fn set_y_label(&mut self, label: &str);
     fn set_caption(&mut self, caption: &str);
     fn set_hold(&mut self, hold: bool);
+    fn graph_2d(&self) -> Option<Graph2DWidget>;
 }
 
 impl std::fmt::Debug for dyn MyWidget {
@@ -16,6 +19,52 @@ impl std::fmt::Debug for dyn MyWidget {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TipMarker {
+    Square,
+    Circle,
+    Cross,
+    None,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TipStyle {
+    pub marker: TipMarker,
+    pub marker_color: Color,
+    pub marker_size: i32,
+    // The label is framed by a box filled with this color, or left transparent for None.
+    pub background: Option<Color>,
+    // Connects the label to the marker.
+    pub leader_line: bool,
+}
+
+impl Default for TipStyle {
+    fn default() -> Self {
+        TipStyle {
+            marker: TipMarker::Square,
+            marker_color: Color::Black,
+            marker_size: 10,
+            background: Some(Color::from_rgb(255, 255, 225)),
+            leader_line: true,
+        }
+    }
+}
+
+// What a data tip formatter gets to build the label of a tip. An interpolated tip lies between
+// two samples, index and metadata are those of the sample closest to it then.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TipInfo<'a> {
+    pub name: &'a str,
+    pub index: usize,
+    pub x: f64,
+    pub y: f64,
+    pub interpolated: bool,
+    pub metadata: Option<&'a str>,
+}
+
+// The label of a tip is placed next to the marker where it covers the least, unless it was
+// moved by hand. Then auto_place is off and its text starts at the pixel offset (lx, ly)
+// from the marker.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct DataTip {
     pub x: f64,
@@ -23,6 +72,8 @@ pub struct DataTip {
     pub plot_idx: usize,
     pub lx: i32,
     pub ly: i32,
+    pub style: TipStyle,
+    pub auto_place: bool,
 }
 
 impl Default for DataTip {
@@ -33,14 +84,41 @@ impl Default for DataTip {
             plot_idx: usize::MAX,
             lx: 0,
             ly: 0,
+            style: TipStyle::default(),
+            auto_place: true,
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Limit {
     pub x_left: f64,
     pub x_right: f64,
     pub y_left: f64,
     pub y_right: f64,
 }
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum AxisScale {
+    #[default]
+    Linear,
+    Log,
+}
+
+impl AxisScale {
+    // Maps a data value to the axis, where the view is linear. Non-positive values have no
+    // place on a log axis and map to NaN or -inf.
+    pub fn forward(&self, value: f64) -> f64 {
+        match self {
+            AxisScale::Linear => value,
+            AxisScale::Log => value.log10(),
+        }
+    }
+
+    pub fn inverse(&self, value: f64) -> f64 {
+        match self {
+            AxisScale::Linear => value,
+            AxisScale::Log => 10f64.powf(value),
+        }
+    }
+}
@@ -1 +1,4 @@
+pub mod figure_layout;
 pub mod figure_window;
+pub mod property_dialog;
+pub mod toolbar;
@@ -0,0 +1,497 @@
+use fltk::{prelude::*, enums::*, draw::*, group::Flex};
+
+use crate::widgets::cursor::StatusLine;
+use crate::widgets::graph_2d_widget::Graph2DWidget;
+use crate::widgets::interaction::InteractionMode;
+
+#[derive(Clone)]
+pub struct LegendEntry {
+    pub name: String,
+    pub style: LineStyle,
+    pub width: i32,
+    pub color: Color,
+}
+
+// Everything needed to position the subplots and the figure-level decorations
+// (title, shared labels and legend) inside a figure window.
+pub struct FigureLayout {
+    pub rows: usize,
+    pub cols: usize,
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub legend: bool,
+    pub subplots: Vec<Option<Graph2DWidget>>,
+    pub spans: Vec<(usize, usize)>,
+    pub row_ratios: Vec<f64>,
+    pub col_ratios: Vec<f64>,
+    pub inner_gap: i32,
+    pub outer_gap: i32,
+    pub insets: Vec<(usize, (f64, f64, f64, f64))>,
+    pub status: Option<StatusLine>,
+    pub toolbar: Option<Flex>,
+    pub mode: InteractionMode,
+}
+
+impl std::fmt::Debug for FigureLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FigureLayout")
+    }
+}
+
+#[allow(dead_code)]
+impl FigureLayout {
+    pub const TOOLBAR_HEIGHT: i32 = 30;
+    const TEXT_SPC: i32 = 6;
+    const LEGEND_LINE: i32 = 25;
+    const LEGEND_SPC: i32 = 15;
+
+    pub fn new(rows: usize, cols: usize) -> FigureLayout {
+        let mut subplots = Vec::with_capacity(rows * cols);
+        subplots.resize_with(rows * cols, || None);
+
+        FigureLayout {
+            rows,
+            cols,
+            title: String::new(),
+            x_label: String::new(),
+            y_label: String::new(),
+            legend: false,
+            subplots,
+            spans: vec![(1, 1); rows * cols],
+            row_ratios: vec![1.0; rows],
+            col_ratios: vec![1.0; cols],
+            inner_gap: 0,
+            outer_gap: 10,
+            insets: Vec::new(),
+            status: None,
+            toolbar: None,
+            mode: InteractionMode::default(),
+        }
+    }
+
+    // Spans are clipped so that they never extend past the last row or column. A span is
+    // refused, and false returned, if it would cover a cell that holds a subplot or belongs
+    // to another span.
+    pub fn set_span(&mut self, subplot: usize, rows: usize, cols: usize) -> bool {
+        if subplot >= self.spans.len() || self.is_covered(subplot) {
+            return false;
+        }
+
+        let row = subplot / self.cols;
+        let column = subplot % self.cols;
+        let span = (
+            rows.clamp(1, self.rows - row),
+            cols.clamp(1, self.cols - column),
+        );
+
+        for r in row..row + span.0 {
+            for c in column..column + span.1 {
+                let cell = r * self.cols + c;
+
+                if cell != subplot
+                    && (self.subplots[cell].is_some()
+                        || self.spans[cell] != (1, 1)
+                        || self.covering_span(cell).is_some_and(|i| i != subplot))
+                {
+                    return false;
+                }
+            }
+        }
+
+        self.spans[subplot] = span;
+        true
+    }
+
+    // Returns the cell whose span covers the given cell, if that is another cell.
+    fn covering_span(&self, subplot: usize) -> Option<usize> {
+        let row = subplot / self.cols;
+        let column = subplot % self.cols;
+
+        self.spans.iter().enumerate().position(|(i, (rows, cols))| {
+            let r = i / self.cols;
+            let c = i % self.cols;
+
+            i != subplot && row >= r && row < r + rows && column >= c && column < c + cols
+        })
+    }
+
+    // Returns true if the cell is part of a span that starts in another cell.
+    pub fn is_covered(&self, subplot: usize) -> bool {
+        self.covering_span(subplot).is_some()
+    }
+
+    // Divides size pixels into parts proportional to ratios, with gap pixels between them.
+    // Returns the offset and length of each part.
+    fn divide(size: i32, ratios: &[f64], gap: i32) -> Vec<(i32, i32)> {
+        let total: f64 = ratios.iter().sum();
+        let available = (size - gap * (ratios.len() as i32 - 1)) as f64;
+        let mut parts = Vec::with_capacity(ratios.len());
+        let mut acc = 0.0;
+
+        for ratio in ratios {
+            let start = (acc / total * available) as i32;
+            acc += ratio;
+            let end = (acc / total * available) as i32;
+
+            parts.push((start + gap * parts.len() as i32, end - start));
+        }
+
+        parts
+    }
+
+    fn toolbar_height(&self) -> i32 {
+        match &self.toolbar {
+            Some(toolbar) if toolbar.visible() => FigureLayout::TOOLBAR_HEIGHT,
+            _ => 0,
+        }
+    }
+
+    fn title_height(&self) -> i32 {
+        if self.title.is_empty() {
+            0
+        } else {
+            set_font(Font::HelveticaBold, 14);
+            height() + FigureLayout::TEXT_SPC
+        }
+    }
+
+    fn x_label_height(&self) -> i32 {
+        if self.x_label.is_empty() {
+            0
+        } else {
+            set_font(Font::Helvetica, 12);
+            height() + FigureLayout::TEXT_SPC
+        }
+    }
+
+    fn y_label_width(&self) -> i32 {
+        if self.y_label.is_empty() {
+            0
+        } else {
+            set_font(Font::Helvetica, 12);
+            height() + FigureLayout::TEXT_SPC
+        }
+    }
+
+    fn status_height(&self) -> i32 {
+        if self.status.is_none() {
+            0
+        } else {
+            set_font(Font::Helvetica, 12);
+            height() + FigureLayout::TEXT_SPC
+        }
+    }
+
+    // Collects the named series of all subplots. Series with the same name share one entry.
+    pub fn legend_entries(&self) -> Vec<LegendEntry> {
+        let mut entries: Vec<LegendEntry> = Vec::new();
+
+        for graph in self.subplots.iter().flatten() {
+            for d in graph.data.borrow().iter().flatten() {
+                if !d.name.is_empty() && !entries.iter().any(|e| e.name == d.name) {
+                    entries.push(LegendEntry {
+                        name: d.name.clone(),
+                        style: d.style,
+                        width: d.width,
+                        color: d.color,
+                    });
+                }
+            }
+        }
+
+        entries
+    }
+
+    // Splits the legend entries into rows that fit in the given width.
+    fn legend_rows(&self, width: i32) -> Vec<Vec<LegendEntry>> {
+        let mut rows: Vec<Vec<LegendEntry>> = Vec::new();
+
+        if !self.legend {
+            return rows;
+        }
+
+        set_font(Font::Helvetica, 12);
+
+        let mut row_width = 0;
+        for entry in self.legend_entries() {
+            let entry_width = FigureLayout::LEGEND_LINE
+                + FigureLayout::TEXT_SPC
+                + measure(&entry.name, false).0
+                + FigureLayout::LEGEND_SPC;
+
+            if rows.is_empty() || row_width + entry_width > width {
+                rows.push(Vec::new());
+                row_width = 0;
+            }
+
+            row_width += entry_width;
+            rows.last_mut().unwrap().push(entry);
+        }
+
+        rows
+    }
+
+    fn legend_height(&self, width: i32) -> i32 {
+        if !self.legend {
+            return 0;
+        }
+
+        set_font(Font::Helvetica, 12);
+        self.legend_rows(width).len() as i32 * (height() + FigureLayout::TEXT_SPC)
+    }
+
+    // Returns the area that is divided among the subplots.
+    pub fn grid_area(&self, w: i32, h: i32) -> (i32, i32, i32, i32) {
+        let m = self.outer_gap;
+        let top = m + self.toolbar_height() + self.title_height();
+        let left = m + self.y_label_width();
+        let bottom =
+            m + self.x_label_height() + self.legend_height(w - 2 * m) + self.status_height();
+
+        (left, top, w - left - m, h - top - bottom)
+    }
+
+    // Returns the cell that belongs to a subplot. The plot widget fills the whole cell
+    // and fits its axes box, caption and labels inside of it.
+    pub fn subplot_area(&self, subplot: usize, w: i32, h: i32) -> (i32, i32, i32, i32) {
+        let (gx, gy, gw, gh) = self.grid_area(w, h);
+        let row = subplot / self.cols;
+        let column = subplot % self.cols;
+        let (rows, cols) = self.spans[subplot];
+
+        let row_parts = FigureLayout::divide(gh, &self.row_ratios, self.inner_gap);
+        let col_parts = FigureLayout::divide(gw, &self.col_ratios, self.inner_gap);
+
+        let (y, _) = row_parts[row];
+        let (x, _) = col_parts[column];
+        let (last_y, last_h) = row_parts[row + rows - 1];
+        let (last_x, last_w) = col_parts[column + cols - 1];
+
+        (gx + x, gy + y, last_x + last_w - x, last_y + last_h - y)
+    }
+
+    // Insets are stored after the grid cells, in the order they were added.
+    pub fn add_inset(
+        &mut self,
+        parent: usize,
+        area: (f64, f64, f64, f64),
+        inset: Graph2DWidget,
+    ) -> usize {
+        self.insets.push((parent, area));
+        self.subplots.push(Some(inset));
+
+        self.subplots.len() - 1
+    }
+
+    pub fn apply(&mut self, w: i32, h: i32) {
+        let cells = self.rows * self.cols;
+
+        if let Some(toolbar) = self.toolbar.as_mut() {
+            toolbar.resize(0, 0, w, FigureLayout::TOOLBAR_HEIGHT);
+        }
+
+        for i in 0..cells {
+            let (x, y, dx, dy) = self.subplot_area(i, w, h);
+
+            if let Some(graph) = self.subplots[i].as_mut() {
+                graph.resize(x, y, dx, dy);
+            }
+        }
+
+        // Insets follow the plot area of their parent, so they are placed after it moved.
+        for (i, (parent, area)) in self.insets.iter().enumerate() {
+            if let Some(parent) = self.subplots[*parent].clone() {
+                let (x, y, dx, dy) = parent.inset_area(*area);
+
+                if let Some(graph) = self.subplots[cells + i].as_mut() {
+                    graph.resize(x, y, dx, dy);
+                }
+            }
+        }
+    }
+
+    pub fn draw(&self, w: i32, h: i32) {
+        let m = self.outer_gap;
+        let (gx, gy, gw, gh) = self.grid_area(w, h);
+
+        set_draw_color(Color::Black);
+        set_line_style(LineStyle::Solid, 1);
+
+        if !self.title.is_empty() {
+            set_font(Font::HelveticaBold, 14);
+            let text_width = width(&self.title) as i32;
+            draw_text(
+                &self.title,
+                gx + gw / 2 - text_width / 2,
+                m + self.toolbar_height() + height() - descent(),
+            );
+        }
+
+        if !self.x_label.is_empty() {
+            set_font(Font::Helvetica, 12);
+            let text_width = width(&self.x_label) as i32;
+            draw_text(
+                &self.x_label,
+                gx + gw / 2 - text_width / 2,
+                gy + gh + height() - descent(),
+            );
+        }
+
+        if !self.y_label.is_empty() {
+            set_font(Font::Helvetica, 12);
+            let text_width = width(&self.y_label) as i32;
+            draw_text_angled(
+                90,
+                &self.y_label,
+                m + height() - descent(),
+                gy + gh / 2 + text_width / 2,
+            );
+        }
+
+        set_font(Font::Helvetica, 12);
+        let line_height = height() + FigureLayout::TEXT_SPC;
+        let mut ly = gy + gh + self.x_label_height();
+
+        for row in self.legend_rows(w - 2 * m) {
+            let row_width: i32 = row
+                .iter()
+                .map(|e| {
+                    FigureLayout::LEGEND_LINE
+                        + FigureLayout::TEXT_SPC
+                        + measure(&e.name, false).0
+                        + FigureLayout::LEGEND_SPC
+                })
+                .sum::<i32>()
+                - FigureLayout::LEGEND_SPC;
+            let mut lx = w / 2 - row_width / 2;
+            let cy = ly + line_height / 2;
+
+            for entry in row {
+                set_draw_color(entry.color);
+                set_line_style(entry.style, entry.width);
+                draw_line(lx, cy, lx + FigureLayout::LEGEND_LINE, cy);
+                set_line_style(LineStyle::Solid, 1);
+
+                lx += FigureLayout::LEGEND_LINE + FigureLayout::TEXT_SPC;
+                set_draw_color(Color::Black);
+                draw_text(&entry.name, lx, cy + height() / 2 - descent());
+                lx += measure(&entry.name, false).0 + FigureLayout::LEGEND_SPC;
+            }
+
+            ly += line_height;
+        }
+
+        // The status area is the bottom line of the window.
+        if let Some(status) = &self.status {
+            set_font(Font::Helvetica, 12);
+            set_draw_color(Color::Black);
+            draw_text(&status.text.borrow(), m, h - m - descent());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Without a title, labels, legend, toolbar or status line no text has to be measured.
+    #[test]
+    fn grid_area_leaves_the_outer_gap() {
+        let layout = FigureLayout::new(1, 1);
+
+        assert_eq!(layout.grid_area(200, 100), (10, 10, 180, 80));
+    }
+
+    #[test]
+    fn subplot_area_of_cells() {
+        let layout = FigureLayout::new(2, 2);
+
+        assert_eq!(layout.subplot_area(0, 200, 100), (10, 10, 90, 40));
+        assert_eq!(layout.subplot_area(3, 200, 100), (100, 50, 90, 40));
+    }
+
+    #[test]
+    fn divide_equal_parts_with_gaps() {
+        assert_eq!(
+            FigureLayout::divide(100, &[1.0, 1.0], 10),
+            vec![(0, 45), (55, 45)]
+        );
+    }
+
+    #[test]
+    fn divide_by_ratios() {
+        assert_eq!(
+            FigureLayout::divide(90, &[2.0, 1.0], 0),
+            vec![(0, 60), (60, 30)]
+        );
+    }
+
+    #[test]
+    fn divide_without_parts() {
+        assert!(FigureLayout::divide(100, &[], 10).is_empty());
+    }
+
+    #[test]
+    fn span_covers_cells() {
+        let mut layout = FigureLayout::new(2, 3);
+
+        assert!(layout.set_span(0, 2, 2));
+        assert_eq!(layout.spans[0], (2, 2));
+        assert!(!layout.is_covered(0));
+        assert!(layout.is_covered(1));
+        assert!(layout.is_covered(3));
+        assert!(layout.is_covered(4));
+        assert!(!layout.is_covered(2));
+        assert!(!layout.is_covered(5));
+    }
+
+    #[test]
+    fn span_is_clipped_to_the_grid() {
+        let mut layout = FigureLayout::new(2, 3);
+
+        assert!(layout.set_span(4, 5, 5));
+        assert_eq!(layout.spans[4], (1, 2));
+        assert!(layout.is_covered(5));
+    }
+
+    #[test]
+    fn span_can_shrink_again() {
+        let mut layout = FigureLayout::new(2, 2);
+
+        assert!(layout.set_span(0, 1, 2));
+        assert!(layout.set_span(0, 1, 1));
+        assert!(!layout.is_covered(1));
+    }
+
+    #[test]
+    fn span_over_another_span_is_refused() {
+        let mut layout = FigureLayout::new(2, 3);
+
+        assert!(layout.set_span(1, 2, 1));
+        assert!(!layout.set_span(0, 1, 2));
+        assert_eq!(layout.spans[0], (1, 1));
+        assert!(!layout.set_span(4, 1, 1));
+    }
+
+    #[test]
+    fn subplot_area_with_gaps_and_spans() {
+        let mut layout = FigureLayout::new(2, 2);
+        layout.outer_gap = 0;
+        layout.inner_gap = 10;
+
+        assert_eq!(layout.subplot_area(0, 210, 110), (0, 0, 100, 50));
+        assert_eq!(layout.subplot_area(3, 210, 110), (110, 60, 100, 50));
+
+        assert!(layout.set_span(0, 1, 2));
+        assert_eq!(layout.subplot_area(0, 210, 110), (0, 0, 210, 50));
+    }
+
+    #[test]
+    fn span_out_of_range_is_refused() {
+        let mut layout = FigureLayout::new(2, 2);
+
+        assert!(!layout.set_span(4, 1, 1));
+        assert!(!layout.is_covered(3));
+    }
+}
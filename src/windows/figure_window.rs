@@ -1,10 +1,22 @@
+use std::cell::RefCell;
 use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 
-use fltk::{prelude::*, enums::Color, draw::*, window::*};
+use fltk::{prelude::*, enums::Color, draw::*, widget::Widget, window::*};
 
+use crate::data::patch_2d_data::Patch2DData;
+use crate::data::plot_2d_data::{Plot2DData, RegionStatistics};
+use crate::widgets::annotation::*;
+use crate::widgets::axis_link::*;
+use crate::widgets::cursor::*;
+use crate::widgets::event::*;
+use crate::widgets::graph_2d_widget::{AxisFormatter, DataCallback, Graph2DWidget, TipFormatter};
 use crate::widgets::plot_2d_widget::Plot2DWidget;
 use crate::widgets::stem_2d_widget::Stem2DWidget;
-use crate::widgets::widget::MyWidget;
+use crate::widgets::widget::{AxisScale, DataTip, Limit, MyWidget, TipInfo, TipStyle};
+use crate::widgets::interaction::{EditConfig, InteractionMode, MouseBinding, TipSnap, WheelConfig};
+use crate::windows::figure_layout::FigureLayout;
+use crate::windows::{property_dialog, toolbar};
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -20,14 +32,15 @@ pub struct FigureWindow {
     pub rows: usize,
     pub cols: usize,
     pub plots: Vec<Option<Box<dyn MyWidget>>>,
+    pub layout: Rc<RefCell<FigureLayout>>,
+    pub decorations: Widget,
+    pub sync_cursor: Option<SyncCursor>,
+    pub crosshair: Option<StatusLine>,
+    pub event_callback: Option<FigureEventCallback>,
 }
 
 #[allow(dead_code)]
 impl FigureWindow {
-    const CAP_SPC: i32 = 25;
-    const XLBL_SPC: i32 = 20;
-    const YLBL_SPC: i32 = 20;
-
     pub fn new(w: i32, h: i32, lb: &str, rows: usize, cols: usize) -> FigureWindow {
         let mut v = Vec::with_capacity(rows * cols);
         v.resize_with(rows * cols, || None::<Box<dyn MyWidget>>);
@@ -38,69 +51,168 @@ impl FigureWindow {
             .center_screen();
         win.make_resizable(true);
 
+        let layout = Rc::from(RefCell::from(FigureLayout::new(rows, cols)));
+
+        // The decorations cover the whole window and are created before any subplot,
+        // so they are drawn below the subplots and never receive their events.
+        let mut decorations = Widget::new(0, 0, w, h, None);
+        let lay = layout.clone();
+        decorations.draw(move |d| {
+            lay.borrow().draw(d.width(), d.height());
+        });
+
+        // Scaling the subplots along with the window would also scale the space reserved
+        // for text, so the layout is redone from scratch instead.
+        let lay = layout.clone();
+        let mut deco = decorations.clone();
+        win.resize_callback(move |_, _, _, w, h| {
+            deco.resize(0, 0, w, h);
+            lay.borrow_mut().apply(w, h);
+        });
+
         FigureWindow {
             window: win,
             plots: v,
             rows,
             cols,
+            layout,
+            decorations,
+            sync_cursor: None,
+            crosshair: None,
+            event_callback: None,
         }
     }
 
+    fn update_layout(&mut self) {
+        let w = self.window.width();
+        let h = self.window.height();
+
+        self.decorations.resize(0, 0, w, h);
+        self.layout.borrow_mut().apply(w, h);
+        self.window.redraw();
+    }
+
+    // Insets are numbered after the grid cells and are always valid once they exist.
     fn is_subplot_index_valid(&self, subplot: usize) -> bool {
-        subplot < (self.rows * self.cols)
+        if subplot < (self.rows * self.cols) {
+            !self.layout.borrow().is_covered(subplot)
+        } else {
+            subplot < self.plots.len()
+        }
     }
 
     fn does_subplot_exist(&self, subplot: usize) -> bool {
         self.is_subplot_index_valid(subplot) && self.plots.get(subplot).unwrap().is_some()
     }
 
-    fn create_subplot(
-        &mut self,
-        x: &[f64],
-        y: &[f64],
-        style: LineStyle,
-        width: i32,
-        color: Color,
-        subplot: usize,
-        plot_type: PlotType,
-    ) {
-        if !self.does_subplot_exist(subplot) {
-            let row = subplot / self.cols;
-            let column = subplot % self.cols;
-            let dx = (self.window.width() - 20) / self.cols as i32;
-            let dy = (self.window.height() - 20) / self.rows as i32;
-            let posx = 10 + dx * column as i32;
-            let posy = 10 + dy * row as i32;
+    fn get_graph_2d(&self, subplot: usize) -> Option<Graph2DWidget> {
+        if self.does_subplot_exist(subplot) {
+            self.plots[subplot].as_ref().unwrap().graph_2d()
+        } else {
+            None
+        }
+    }
+
+    fn create_subplot(&mut self, subplot: usize, plot_type: PlotType) {
+        if self.is_subplot_index_valid(subplot) && !self.does_subplot_exist(subplot) {
+            let (x, y, w, h) = self.layout.borrow().subplot_area(
+                subplot,
+                self.window.width(),
+                self.window.height(),
+            );
 
             self.window.begin();
 
             match plot_type {
                 PlotType::Plot => {
-                    let mut plot_widget: Box<dyn MyWidget> = Box::new(Plot2DWidget::new(
-                        posx + FigureWindow::YLBL_SPC,
-                        posy + FigureWindow::CAP_SPC,
-                        dx - FigureWindow::YLBL_SPC,
-                        dy - (FigureWindow::CAP_SPC + FigureWindow::XLBL_SPC),
-                        "Subfig",
-                    ));
-                    plot_widget.put_data(x, y, style, width, color);
+                    let plot_widget: Box<dyn MyWidget> =
+                        Box::new(Plot2DWidget::new(x, y, w, h, "Subfig"));
                     self.plots[subplot] = Some(plot_widget);
                 }
                 PlotType::Stem => {
-                    let mut stem_widget: Box<dyn MyWidget> = Box::new(Stem2DWidget::new(
-                        posx + FigureWindow::YLBL_SPC,
-                        posy + FigureWindow::CAP_SPC,
-                        dx - FigureWindow::YLBL_SPC,
-                        dy - (FigureWindow::CAP_SPC + FigureWindow::XLBL_SPC),
-                        "Subfig",
-                    ));
-                    stem_widget.put_data(x, y, style, width, color);
+                    let stem_widget: Box<dyn MyWidget> =
+                        Box::new(Stem2DWidget::new(x, y, w, h, "Subfig"));
                     self.plots[subplot] = Some(stem_widget);
                 }
                 PlotType::Quiver => {}
             }
 
             self.window.end();
+
+            if let Some(mut graph) = self.get_graph_2d(subplot) {
+                graph.set_sync_cursor(self.sync_cursor.clone());
+                graph.set_crosshair(self.crosshair.clone());
+                graph.set_mode(self.layout.borrow().mode);
+                graph.set_event_callback(
+                    self.event_callback.as_ref().map(|c| c.for_subplot(subplot)),
+                );
+                self.layout.borrow_mut().subplots[subplot] = Some(graph);
+            }
+        }
+    }
+
+    // Adds a small axes inside the plot area of the parent subplot. The area is given in
+    // the parent's axes coordinates as (x, y, width, height), with (x, y) the bottom left
+    // corner. Returns the subplot index of the inset, which works with all other methods.
+    pub fn add_inset(&mut self, parent: usize, area: (f64, f64, f64, f64)) -> Option<usize> {
+        let parent_graph = self.get_graph_2d(parent)?;
+        let (x, y, w, h) = parent_graph.inset_area(area);
+
+        self.window.begin();
+        let inset: Box<dyn MyWidget> = Box::new(Plot2DWidget::new(x, y, w, h, ""));
+        self.window.end();
+
+        let mut graph = inset.graph_2d().unwrap();
+        graph.set_sync_cursor(self.sync_cursor.clone());
+        graph.set_crosshair(self.crosshair.clone());
+        graph.set_mode(self.layout.borrow().mode);
+
+        let index = self
+            .layout
+            .borrow_mut()
+            .add_inset(parent, area, graph.clone());
+        graph.set_event_callback(self.event_callback.as_ref().map(|c| c.for_subplot(index)));
+        self.plots.push(Some(inset));
+        self.window.redraw();
+
+        Some(index)
+    }
+
+    // Shows the given region of the parent subplot in the inset and marks it on the parent.
+    pub fn link_inset(&mut self, inset: usize, region: Limit) {
+        let parent = inset.checked_sub(self.rows * self.cols).and_then(|i| {
+            self.layout
+                .borrow()
+                .insets
+                .get(i)
+                .map(|(parent, _)| *parent)
+        });
+
+        if let (Some(mut parent), Some(mut inset)) = (
+            parent.and_then(|p| self.get_graph_2d(p)),
+            self.get_graph_2d(inset),
+        ) {
+            parent.link_inset(&mut inset, region);
+        }
+    }
+
+    // Keeps the view limits of the given subplots in sync along the given axes. The
+    // subplots take over the current view of the first one.
+    pub fn link_axes(&mut self, subplots: &[usize], axis: LinkAxis) -> AxisLinkGroup {
+        let group = AxisLinkGroup::new(axis);
+
+        for subplot in subplots {
+            self.join_link_group(&group, *subplot);
+        }
+
+        group
+    }
+
+    // Adds a subplot to an existing link group, which may also contain subplots of other
+    // figure windows. The subplot takes over the view of the group.
+    pub fn join_link_group(&mut self, group: &AxisLinkGroup, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.link_axes(group);
         }
     }
 
@@ -132,10 +244,10 @@ impl FigureWindow {
         subplot: usize,
     ) {
         if !self.does_subplot_exist(subplot) {
-            self.create_subplot(x, y, style, width, color, subplot, PlotType::Plot);
-        } else {
-            self.update_subplot(x, y, style, width, color, subplot);
+            self.create_subplot(subplot, PlotType::Plot);
         }
+
+        self.update_subplot(x, y, style, width, color, subplot);
     }
 
     pub fn stem(
@@ -148,9 +260,69 @@ impl FigureWindow {
         subplot: usize,
     ) {
         if !self.does_subplot_exist(subplot) {
-            self.create_subplot(x, y, style, width, color, subplot, PlotType::Stem);
-        } else {
-            self.update_subplot(x, y, style, width, color, subplot);
+            self.create_subplot(subplot, PlotType::Stem);
+        }
+
+        self.update_subplot(x, y, style, width, color, subplot);
+    }
+
+    pub fn add_patch(&mut self, patch: Patch2DData, subplot: usize) {
+        if !self.does_subplot_exist(subplot) {
+            self.create_subplot(subplot, PlotType::Plot);
+        }
+
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.put_patch(patch);
+        }
+    }
+
+    pub fn patch(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        face_color: Option<Color>,
+        edge_color: Option<Color>,
+        subplot: usize,
+    ) {
+        self.add_patch(
+            Patch2DData::new(x, y, face_color, edge_color, LineStyle::Solid, 1),
+            subplot,
+        );
+    }
+
+    pub fn rectangle(
+        &mut self,
+        corner: (f64, f64),
+        width: f64,
+        height: f64,
+        face_color: Option<Color>,
+        edge_color: Option<Color>,
+        subplot: usize,
+    ) {
+        self.add_patch(
+            Patch2DData::rectangle(corner.0, corner.1, width, height, face_color, edge_color),
+            subplot,
+        );
+    }
+
+    pub fn ellipse(
+        &mut self,
+        center: (f64, f64),
+        rx: f64,
+        ry: f64,
+        face_color: Option<Color>,
+        edge_color: Option<Color>,
+        subplot: usize,
+    ) {
+        self.add_patch(
+            Patch2DData::ellipse(center.0, center.1, rx, ry, face_color, edge_color),
+            subplot,
+        );
+    }
+
+    pub fn clear_patches(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.clear_patches();
         }
     }
 
@@ -208,6 +380,612 @@ impl FigureWindow {
                 .set_hold(on);
         }
     }
+
+    pub fn set_series_names(&mut self, names: &[&str], subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_series_names(names);
+            self.update_layout();
+        }
+    }
+
+    pub fn set_legend(&mut self, on: bool, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_legend(on);
+        }
+    }
+
+    // A log axis needs positive limits, a lower limit that is not moves to the smallest
+    // positive value of the data.
+    pub fn set_axis_scales(&mut self, x: AxisScale, y: AxisScale, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_scales(x, y);
+        }
+    }
+
+    pub fn get_axis_scales(&self, subplot: usize) -> Option<(AxisScale, AxisScale)> {
+        self.get_graph_2d(subplot).map(|graph| graph.scales())
+    }
+
+    // Opens the property dialog of the subplot, the same as its context menu does. The
+    // returned window is deleted when the dialog is closed.
+    pub fn edit_properties(&self, subplot: usize) -> Option<Window> {
+        self.get_graph_2d(subplot)
+            .map(|graph| property_dialog::show_property_dialog(&graph))
+    }
+
+    pub fn export_csv<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        subplot: usize,
+    ) -> std::io::Result<()> {
+        match self.get_graph_2d(subplot) {
+            Some(graph) => graph.write_csv(path.as_ref()),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "subplot does not exist",
+            )),
+        }
+    }
+
+    // Lets a subplot cover multiple cells of the grid, starting at the subplot's own cell.
+    // The other cells it covers can no longer be used for subplots of their own. Returns
+    // false and leaves the layout unchanged if one of those cells already has a subplot or
+    // is part of another span.
+    pub fn set_span(&mut self, subplot: usize, rows: usize, cols: usize) -> bool {
+        if !self.is_subplot_index_valid(subplot) {
+            return false;
+        }
+
+        let changed = self.layout.borrow_mut().set_span(subplot, rows, cols);
+        if changed {
+            self.update_layout();
+        }
+
+        changed
+    }
+
+    pub fn set_row_ratios(&mut self, ratios: &[f64]) {
+        if ratios.len() == self.rows && ratios.iter().all(|r| *r > 0.0) {
+            self.layout.borrow_mut().row_ratios = ratios.to_vec();
+            self.update_layout();
+        }
+    }
+
+    pub fn set_col_ratios(&mut self, ratios: &[f64]) {
+        if ratios.len() == self.cols && ratios.iter().all(|r| *r > 0.0) {
+            self.layout.borrow_mut().col_ratios = ratios.to_vec();
+            self.update_layout();
+        }
+    }
+
+    // inner is the space between neighbouring cells, outer the space around the figure.
+    pub fn set_gaps(&mut self, inner: i32, outer: i32) {
+        self.layout.borrow_mut().inner_gap = i32::max(inner, 0);
+        self.layout.borrow_mut().outer_gap = i32::max(outer, 0);
+        self.update_layout();
+    }
+
+    // Shows a vertical cursor that follows the mouse in one subplot at the same x in all
+    // subplots, along with the value of each series at that x.
+    pub fn set_sync_cursor(&mut self, on: bool) {
+        self.sync_cursor = if on { Some(SyncCursor::new()) } else { None };
+
+        for subplot in 0..self.plots.len() {
+            if let Some(mut graph) = self.get_graph_2d(subplot) {
+                graph.set_sync_cursor(self.sync_cursor.clone());
+            }
+        }
+    }
+
+    // Shows a row of buttons at the top of the window to select the interaction mode,
+    // reset the view of all subplots and export the figure.
+    pub fn set_toolbar(&mut self, on: bool) {
+        let exists = self.layout.borrow().toolbar.is_some();
+
+        if on && !exists {
+            self.window.begin();
+            let bar = toolbar::create_toolbar(self.layout.clone(), self.window.clone());
+            self.window.end();
+
+            self.layout.borrow_mut().toolbar = Some(bar);
+        } else if let Some(bar) = self.layout.borrow_mut().toolbar.as_mut() {
+            if on {
+                bar.show();
+            } else {
+                bar.hide();
+            }
+        }
+
+        self.update_layout();
+    }
+
+    // Selects what dragging with the left mouse button does in all subplots.
+    pub fn set_mode(&mut self, mode: InteractionMode) {
+        toolbar::set_mode(&self.layout, mode);
+    }
+
+    // Returns the subplot to the view before the last zoom or pan. Returns false if there
+    // is no earlier view.
+    pub fn view_back(&mut self, subplot: usize) -> bool {
+        self.get_graph_2d(subplot)
+            .map(|mut graph| graph.view_back())
+            .unwrap_or(false)
+    }
+
+    pub fn view_forward(&mut self, subplot: usize) -> bool {
+        self.get_graph_2d(subplot)
+            .map(|mut graph| graph.view_forward())
+            .unwrap_or(false)
+    }
+
+    pub fn clear_view_history(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.clear_history();
+        }
+    }
+
+    // Selects whether the mouse wheel zooms or pans in the subplot, and how far it goes.
+    pub fn set_wheel_config(&mut self, config: WheelConfig, subplot: usize) {
+        if let Some(graph) = self.get_graph_2d(subplot) {
+            *graph.wheel.borrow_mut() = config;
+        }
+    }
+
+    // Assigns an action to a mouse button, modifier and gesture combination. Binding
+    // MouseAction::None leaves the combination to the application.
+    pub fn bind_mouse(&mut self, binding: MouseBinding, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.bind_mouse(binding);
+        }
+    }
+
+    pub fn reset_mouse_bindings(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.reset_mouse_bindings();
+        }
+    }
+
+    pub fn get_mouse_bindings(&self, subplot: usize) -> Vec<MouseBinding> {
+        self.get_graph_2d(subplot)
+            .map(|graph| graph.bindings.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    // Constrains dragging points in the EditData mode.
+    pub fn set_edit_config(&mut self, config: EditConfig, subplot: usize) {
+        if let Some(graph) = self.get_graph_2d(subplot) {
+            *graph.edit.borrow_mut() = config;
+        }
+    }
+
+    // Called with the series index and the new data whenever a point of the subplot is
+    // moved, inserted or deleted.
+    pub fn set_data_callback<F: Fn(usize, &Plot2DData) + 'static>(
+        &mut self,
+        callback: F,
+        subplot: usize,
+    ) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_data_callback(Some(Rc::new(callback) as DataCallback));
+        }
+    }
+
+    pub fn clear_data_callback(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_data_callback(None);
+        }
+    }
+
+    pub fn insert_point(&mut self, x: f64, y: f64, series: usize, subplot: usize) -> Option<usize> {
+        self.get_graph_2d(subplot)?.insert_point(series, x, y)
+    }
+
+    pub fn delete_point(
+        &mut self,
+        index: usize,
+        series: usize,
+        subplot: usize,
+    ) -> Option<(f64, f64)> {
+        self.get_graph_2d(subplot)?.delete_point(series, index)
+    }
+
+    // Returns the x and y values of a series, including any edits.
+    pub fn get_data(&self, series: usize, subplot: usize) -> Option<(Vec<f64>, Vec<f64>)> {
+        let graph = self.get_graph_2d(subplot)?;
+        let data = graph.data.borrow();
+        let d = data.get(series)?.as_ref()?;
+
+        Some((d.x.clone(), d.y.clone()))
+    }
+
+    // Adds a data tip on a sample of a series. Returns the index of the tip.
+    pub fn add_data_tip(&mut self, series: usize, sample: usize, subplot: usize) -> Option<usize> {
+        self.get_graph_2d(subplot)?.add_data_tip(series, sample)
+    }
+
+    pub fn get_data_tips(&self, subplot: usize) -> Vec<DataTip> {
+        self.get_graph_2d(subplot)
+            .map(|graph| graph.data_tips.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    // Moves a data tip to another sample of its series.
+    pub fn move_data_tip(&mut self, index: usize, sample: usize, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.move_data_tip_to_sample(index, sample);
+        }
+    }
+
+    pub fn remove_data_tip(&mut self, index: usize, subplot: usize) -> Option<DataTip> {
+        self.get_graph_2d(subplot)?.remove_data_tip(index)
+    }
+
+    pub fn clear_data_tips(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.clear_data_tips();
+        }
+    }
+
+    // Places the label of a data tip at a pixel offset from its marker.
+    pub fn set_data_tip_label_offset(&mut self, index: usize, lx: i32, ly: i32, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_tip_label_offset(index, lx, ly);
+        }
+    }
+
+    // Places the label of a data tip automatically again, after it was given an offset.
+    pub fn set_data_tip_auto_place(&mut self, index: usize, on: bool, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_tip_auto_place(index, on);
+        }
+    }
+
+    pub fn set_data_tip_style(&mut self, index: usize, style: TipStyle, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_tip_style(index, style);
+        }
+    }
+
+    // The style of the data tips added afterwards, by the mouse or by add_data_tip.
+    pub fn set_default_tip_style(&mut self, style: TipStyle, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_default_tip_style(style);
+        }
+    }
+
+    // Builds the labels of the data tips of a series. Every line of the returned text is a
+    // line of the label.
+    pub fn set_tip_formatter<F: Fn(&TipInfo) -> String + 'static>(
+        &mut self,
+        formatter: F,
+        series: usize,
+        subplot: usize,
+    ) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_tip_formatter(series, Some(Rc::new(formatter) as TipFormatter));
+        }
+    }
+
+    pub fn clear_tip_formatter(&mut self, series: usize, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_tip_formatter(series, None);
+        }
+    }
+
+    // Extra text for each sample of a series, passed to its tip formatter.
+    pub fn set_point_metadata(&mut self, metadata: Vec<String>, series: usize, subplot: usize) {
+        if let Some(graph) = self.get_graph_2d(subplot) {
+            if let Some(Some(d)) = graph.data.borrow_mut().get_mut(series) {
+                d.metadata = metadata;
+            }
+        }
+    }
+
+    // Whether data tips snap to the nearest sample or to any point of the line.
+    pub fn set_tip_snap(&mut self, snap: TipSnap, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_tip_snap(snap);
+        }
+    }
+
+    pub fn get_mode(&self) -> InteractionMode {
+        self.layout.borrow().mode
+    }
+
+    pub fn export_svg<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let top = match self.layout.borrow().toolbar.as_ref() {
+            Some(bar) if bar.visible() => FigureLayout::TOOLBAR_HEIGHT,
+            _ => 0,
+        };
+
+        toolbar::write_svg(&self.window, top, path.as_ref())
+    }
+
+    // Replaces the mouse pointer over the plot areas with a crosshair and shows the data
+    // coordinates under it in a status area at the bottom of the window.
+    pub fn set_crosshair(&mut self, on: bool) {
+        self.crosshair = if on { Some(StatusLine::new()) } else { None };
+        self.layout.borrow_mut().status = self.crosshair.clone();
+
+        for subplot in 0..self.plots.len() {
+            if let Some(mut graph) = self.get_graph_2d(subplot) {
+                graph.set_crosshair(self.crosshair.clone());
+            }
+        }
+
+        self.update_layout();
+    }
+
+    // Called with the subplot index for everything that happens in the subplots: view
+    // changes, data tips, clicked points, selections and mouse moves.
+    pub fn set_event_callback<F: Fn(usize, &PlotEvent) + 'static>(&mut self, callback: F) {
+        self.event_callback = Some(FigureEventCallback(Rc::new(callback)));
+        self.apply_event_callback();
+    }
+
+    pub fn clear_event_callback(&mut self) {
+        self.event_callback = None;
+        self.apply_event_callback();
+    }
+
+    fn apply_event_callback(&mut self) {
+        for subplot in 0..self.plots.len() {
+            if let Some(mut graph) = self.get_graph_2d(subplot) {
+                graph.set_event_callback(
+                    self.event_callback.as_ref().map(|c| c.for_subplot(subplot)),
+                );
+            }
+        }
+    }
+
+    // Formats the tick labels and coordinate readouts of the x-axis.
+    pub fn set_x_formatter<F: Fn(f64) -> String + 'static>(
+        &mut self,
+        formatter: F,
+        subplot: usize,
+    ) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            let y = graph.y_formatter.borrow().clone();
+            graph.set_formatters(Some(Rc::new(formatter) as AxisFormatter), y);
+        }
+    }
+
+    // Formats the tick labels and coordinate readouts of the y-axis.
+    pub fn set_y_formatter<F: Fn(f64) -> String + 'static>(
+        &mut self,
+        formatter: F,
+        subplot: usize,
+    ) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            let x = graph.x_formatter.borrow().clone();
+            graph.set_formatters(x, Some(Rc::new(formatter) as AxisFormatter));
+        }
+    }
+
+    pub fn clear_formatters(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_formatters(None, None);
+        }
+    }
+
+    // Shows two draggable vertical cursors, and optionally two horizontal ones, with a
+    // readout of their positions and of the series values at the vertical cursors.
+    pub fn show_measure_cursors(&mut self, on: bool, horizontal: bool, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            if on {
+                graph.show_measure_cursors(horizontal);
+            } else {
+                graph.set_measure_cursors(None);
+            }
+        }
+    }
+
+    pub fn set_measure_cursors(&mut self, cursors: MeasureCursors, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_measure_cursors(Some(cursors));
+        }
+    }
+
+    pub fn get_measure_cursors(&self, subplot: usize) -> Option<MeasureCursors> {
+        self.get_graph_2d(subplot)
+            .and_then(|graph| *graph.measure_cursors.borrow())
+    }
+
+    // Returns the index of each visible series with its values at the x1 and x2 cursors.
+    pub fn get_measure_values(&self, subplot: usize) -> Vec<(usize, Option<f64>, Option<f64>)> {
+        self.get_graph_2d(subplot)
+            .map(|graph| graph.measure_values())
+            .unwrap_or_default()
+    }
+
+    // While the region tool is on, dragging with the left mouse button selects the
+    // x-range over which the statistics of each series are shown.
+    pub fn set_region_tool(&mut self, on: bool, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_region_tool(on);
+        }
+    }
+
+    pub fn set_stats_region(&mut self, x_left: f64, x_right: f64, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_stats_region(Some((x_left, x_right)));
+        }
+    }
+
+    pub fn clear_stats_region(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.set_stats_region(None);
+        }
+    }
+
+    pub fn get_stats_region(&self, subplot: usize) -> Option<(f64, f64)> {
+        self.get_graph_2d(subplot)
+            .and_then(|graph| *graph.stats_region.borrow())
+    }
+
+    // Returns the index and the statistics of each visible series over the selected region.
+    pub fn get_region_statistics(&self, subplot: usize) -> Vec<(usize, Option<RegionStatistics>)> {
+        self.get_graph_2d(subplot)
+            .map(|graph| graph.region_statistics())
+            .unwrap_or_default()
+    }
+
+    pub fn set_super_title(&mut self, title: &str) {
+        self.layout.borrow_mut().title = title.to_string();
+        self.update_layout();
+    }
+
+    pub fn set_super_x_label(&mut self, label: &str) {
+        self.layout.borrow_mut().x_label = label.to_string();
+        self.update_layout();
+    }
+
+    pub fn set_super_y_label(&mut self, label: &str) {
+        self.layout.borrow_mut().y_label = label.to_string();
+        self.update_layout();
+    }
+
+    pub fn set_figure_legend(&mut self, on: bool) {
+        self.layout.borrow_mut().legend = on;
+        self.update_layout();
+    }
+
+    pub fn annotate(&mut self, annotation: Annotation, subplot: usize) -> Option<usize> {
+        self.get_graph_2d(subplot)
+            .map(|mut graph| graph.add_annotation(annotation))
+    }
+
+    pub fn text(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: &str,
+        coords: CoordinateSystem,
+        color: Color,
+        subplot: usize,
+    ) -> Option<usize> {
+        self.annotate(
+            Annotation::Text {
+                x,
+                y,
+                coords,
+                text: text.to_string(),
+                color,
+            },
+            subplot,
+        )
+    }
+
+    pub fn arrow(
+        &mut self,
+        from: (f64, f64),
+        to: (f64, f64),
+        coords: CoordinateSystem,
+        width: i32,
+        color: Color,
+        subplot: usize,
+    ) -> Option<usize> {
+        self.annotate(
+            Annotation::Arrow {
+                x0: from.0,
+                y0: from.1,
+                x1: to.0,
+                y1: to.1,
+                coords,
+                width,
+                color,
+            },
+            subplot,
+        )
+    }
+
+    pub fn xline(
+        &mut self,
+        x: f64,
+        label: &str,
+        style: LineStyle,
+        width: i32,
+        color: Color,
+        subplot: usize,
+    ) -> Option<usize> {
+        self.annotate(
+            Annotation::XLine {
+                x,
+                label: label.to_string(),
+                style,
+                width,
+                color,
+            },
+            subplot,
+        )
+    }
+
+    pub fn yline(
+        &mut self,
+        y: f64,
+        label: &str,
+        style: LineStyle,
+        width: i32,
+        color: Color,
+        subplot: usize,
+    ) -> Option<usize> {
+        self.annotate(
+            Annotation::YLine {
+                y,
+                label: label.to_string(),
+                style,
+                width,
+                color,
+            },
+            subplot,
+        )
+    }
+
+    pub fn xregion(
+        &mut self,
+        x_left: f64,
+        x_right: f64,
+        color: Color,
+        subplot: usize,
+    ) -> Option<usize> {
+        self.annotate(
+            Annotation::XRegion {
+                x_left,
+                x_right,
+                color,
+            },
+            subplot,
+        )
+    }
+
+    pub fn yregion(
+        &mut self,
+        y_left: f64,
+        y_right: f64,
+        color: Color,
+        subplot: usize,
+    ) -> Option<usize> {
+        self.annotate(
+            Annotation::YRegion {
+                y_left,
+                y_right,
+                color,
+            },
+            subplot,
+        )
+    }
+
+    pub fn remove_annotation(&mut self, index: usize, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.remove_annotation(index);
+        }
+    }
+
+    pub fn clear_annotations(&mut self, subplot: usize) {
+        if let Some(mut graph) = self.get_graph_2d(subplot) {
+            graph.clear_annotations();
+        }
+    }
 }
 
 impl Deref for FigureWindow {
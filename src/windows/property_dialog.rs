@@ -0,0 +1,344 @@
+use fltk::{
+    prelude::*, button::*, dialog::*, draw::LineStyle, enums::*, frame::Frame, group::*, input::*,
+    menu::Choice, misc::Spinner, window::*,
+};
+
+use crate::widgets::graph_2d_widget::Graph2DWidget;
+use crate::widgets::widget::{AxisScale, Limit, MyWidget};
+
+// The line styles offered for the series, in the order of the choice.
+const STYLES: [(LineStyle, &str); 5] = [
+    (LineStyle::Solid, "Solid"),
+    (LineStyle::Dash, "Dash"),
+    (LineStyle::Dot, "Dot"),
+    (LineStyle::DashDot, "Dash dot"),
+    (LineStyle::DashDotDot, "Dash dot dot"),
+];
+
+const LABEL_WIDTH: i32 = 70;
+const ROW_HEIGHT: i32 = 25;
+const WIDTH: i32 = 360;
+const HEIGHT: i32 = 390;
+
+// A row of the dialog, with a label in front of the widgets that are added to it.
+fn labeled_row(label: &str) -> Flex {
+    let mut row = Flex::default().row();
+    let frame = Frame::default()
+        .with_label(label)
+        .with_align(Align::Left | Align::Inside);
+    row.fixed(&frame, LABEL_WIDTH);
+    row
+}
+
+fn end_row(column: &mut Flex, row: Flex) {
+    row.end();
+    column.fixed(&row, ROW_HEIGHT);
+}
+
+// Text inputs apply every change immediately.
+fn text_input(column: &mut Flex, label: &str, value: &str) -> Input {
+    let row = labeled_row(label);
+    let mut input = Input::default();
+    input.set_value(value);
+    input.set_trigger(CallbackTrigger::Changed);
+    end_row(column, row);
+
+    input
+}
+
+// Limits are applied when Enter is pressed or the input loses the focus, so the view does
+// not jump around while a number is typed.
+fn limit_inputs(column: &mut Flex, label: &str, left: f64, right: f64) -> (FloatInput, FloatInput) {
+    let row = labeled_row(label);
+    let mut inputs = (FloatInput::default(), FloatInput::default());
+
+    for (input, value) in [(&mut inputs.0, left), (&mut inputs.1, right)] {
+        input.set_value(&value.to_string());
+        input.set_trigger(CallbackTrigger::EnterKeyAlways | CallbackTrigger::ReleaseAlways);
+    }
+    end_row(column, row);
+
+    inputs
+}
+
+// Menu labels treat a slash as a submenu separator.
+fn menu_label(label: &str) -> String {
+    label.replace('/', "\\/")
+}
+
+#[derive(Clone)]
+struct LimitEditor {
+    x: (FloatInput, FloatInput),
+    y: (FloatInput, FloatInput),
+}
+
+impl LimitEditor {
+    fn load(&mut self, limit: &Limit) {
+        self.x.0.set_value(&limit.x_left.to_string());
+        self.x.1.set_value(&limit.x_right.to_string());
+        self.y.0.set_value(&limit.y_left.to_string());
+        self.y.1.set_value(&limit.y_right.to_string());
+    }
+
+    // Sets the view of the graph to the limits in the inputs. Limits that are not numbers,
+    // are in the wrong order or are not positive on a log axis are replaced by the current
+    // view instead.
+    fn apply(&mut self, graph: &mut Graph2DWidget) {
+        let before = *graph.limit_c.borrow();
+        let (x_scale, y_scale) = graph.scales();
+        let parse = |input: &FloatInput| input.value().trim().parse::<f64>().ok();
+        let valid = |scale: AxisScale, left: f64, right: f64| {
+            left.is_finite()
+                && right.is_finite()
+                && left < right
+                && (scale == AxisScale::Linear || left > 0.0)
+        };
+
+        let mut limit = before;
+        if let (Some(left), Some(right)) = (parse(&self.x.0), parse(&self.x.1)) {
+            if valid(x_scale, left, right) {
+                limit.x_left = left;
+                limit.x_right = right;
+            }
+        }
+        if let (Some(left), Some(right)) = (parse(&self.y.0), parse(&self.y.1)) {
+            if valid(y_scale, left, right) {
+                limit.y_left = left;
+                limit.y_right = right;
+            }
+        }
+
+        *graph.limit_c.borrow_mut() = limit;
+        graph.propagate_limits();
+        graph.record_view(before);
+        self.load(&limit);
+        fltk::app::redraw();
+    }
+}
+
+#[derive(Clone)]
+struct SeriesEditor {
+    // Data indices of the entries of the series choice, empty slots are left out.
+    indices: Vec<usize>,
+    series: Choice,
+    color: Button,
+    style: Choice,
+    width: Spinner,
+    visible: CheckButton,
+}
+
+impl SeriesEditor {
+    fn selected(&self) -> Option<usize> {
+        usize::try_from(self.series.value())
+            .ok()
+            .and_then(|i| self.indices.get(i).copied())
+    }
+
+    fn load(&mut self, graph: &Graph2DWidget) {
+        let data = graph.data.borrow();
+
+        if let Some(Some(d)) = self.selected().and_then(|i| data.get(i)) {
+            let style = STYLES.iter().position(|(s, _)| *s == d.style).unwrap_or(0);
+
+            self.color.set_color(d.color);
+            self.style.set_value(style as i32);
+            self.width.set_value(d.width as f64);
+            self.visible.set_checked(d.visible);
+            self.color.redraw();
+        }
+    }
+
+    fn apply(&self, graph: &Graph2DWidget) {
+        let mut data = graph.data.borrow_mut();
+
+        if let Some(Some(d)) = self.selected().and_then(|i| data.get_mut(i)) {
+            d.color = self.color.color();
+            d.style = usize::try_from(self.style.value())
+                .ok()
+                .and_then(|i| STYLES.get(i))
+                .map(|(s, _)| *s)
+                .unwrap_or(LineStyle::Solid);
+            d.width = self.width.value() as i32;
+            d.visible = self.visible.is_checked();
+        }
+
+        fltk::app::redraw();
+    }
+}
+
+// Opens a window to edit the caption, labels, limits, grid and series of a graph. All
+// changes are applied to the graph right away, closing the window keeps them.
+pub fn show_property_dialog(graph: &Graph2DWidget) -> Window {
+    // The dialog is a window of its own, not a child of whatever group is being built.
+    // That group is made current again once the dialog is built.
+    let previous = Group::try_current();
+    Group::set_current(None::<&Group>);
+
+    let mut window = Window::default()
+        .with_size(WIDTH, HEIGHT)
+        .with_label("Properties")
+        .center_screen();
+    let mut column = Flex::default_fill().column();
+    column.set_margin(10);
+    column.set_pad(5);
+
+    let mut caption = text_input(&mut column, "Caption", &graph.caption.borrow());
+    let mut x_label = text_input(&mut column, "X label", &graph.x_label.borrow());
+    let mut y_label = text_input(&mut column, "Y label", &graph.y_label.borrow());
+
+    let limit_c = *graph.limit_c.borrow();
+    let limits = LimitEditor {
+        x: limit_inputs(&mut column, "X limits", limit_c.x_left, limit_c.x_right),
+        y: limit_inputs(&mut column, "Y limits", limit_c.y_left, limit_c.y_right),
+    };
+
+    let row = labeled_row("Grid");
+    let mut grid = CheckButton::default().with_label("Show");
+    grid.set_checked(*graph.grid.borrow());
+    let mut x_ticks = Spinner::default();
+    let mut y_ticks = Spinner::default();
+    for (spinner, value, tooltip) in [
+        (&mut x_ticks, *graph.xn_grid.borrow(), "Number of x ticks"),
+        (&mut y_ticks, *graph.yn_grid.borrow(), "Number of y ticks"),
+    ] {
+        spinner.set_range(0.0, 20.0);
+        spinner.set_step(1.0);
+        spinner.set_value(value as f64);
+        spinner.set_tooltip(tooltip);
+    }
+    end_row(&mut column, row);
+
+    let row = labeled_row("Series");
+    let mut series = Choice::default();
+    end_row(&mut column, row);
+
+    let row = labeled_row("Color");
+    let mut color = Button::default();
+    color.set_frame(FrameType::DownBox);
+    end_row(&mut column, row);
+
+    let row = labeled_row("Style");
+    let mut style = Choice::default();
+    for (_, name) in STYLES.iter() {
+        style.add_choice(name);
+    }
+    end_row(&mut column, row);
+
+    let row = labeled_row("Width");
+    let mut width = Spinner::default();
+    width.set_range(1.0, 10.0);
+    width.set_step(1.0);
+    end_row(&mut column, row);
+
+    let row = labeled_row("");
+    let visible = CheckButton::default().with_label("Visible");
+    end_row(&mut column, row);
+
+    // Spacer above the close button.
+    Frame::default();
+
+    let mut row = Flex::default().row();
+    Frame::default();
+    let mut close = Button::default().with_label("Close");
+    row.fixed(&close, 80);
+    end_row(&mut column, row);
+
+    column.end();
+    window.end();
+    Group::set_current(previous.as_ref());
+
+    let indices: Vec<usize> = graph
+        .data
+        .borrow()
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.is_some())
+        .map(|(i, _)| i)
+        .collect();
+    for i in indices.iter() {
+        series.add_choice(&menu_label(&graph.series_label(*i)));
+    }
+
+    let mut editor = SeriesEditor {
+        indices,
+        series,
+        color,
+        style,
+        width,
+        visible,
+    };
+
+    if editor.indices.is_empty() {
+        editor.series.deactivate();
+        editor.color.deactivate();
+        editor.style.deactivate();
+        editor.width.deactivate();
+        editor.visible.deactivate();
+    } else {
+        editor.series.set_value(0);
+        editor.load(graph);
+    }
+
+    let mut g = graph.clone();
+    caption.set_callback(move |i| g.set_caption(&i.value()));
+    let mut g = graph.clone();
+    x_label.set_callback(move |i| g.set_x_label(&i.value()));
+    let mut g = graph.clone();
+    y_label.set_callback(move |i| g.set_y_label(&i.value()));
+
+    for mut input in [
+        limits.x.0.clone(),
+        limits.x.1.clone(),
+        limits.y.0.clone(),
+        limits.y.1.clone(),
+    ] {
+        let mut g = graph.clone();
+        let mut l = limits.clone();
+        input.set_callback(move |_| l.apply(&mut g));
+    }
+
+    let mut g = graph.clone();
+    grid.set_callback(move |b| g.set_grid(b.is_checked()));
+
+    for (mut spinner, n_grid) in [
+        (x_ticks, graph.xn_grid.clone()),
+        (y_ticks, graph.yn_grid.clone()),
+    ] {
+        spinner.set_callback(move |s| {
+            *n_grid.borrow_mut() = s.value() as i32;
+            fltk::app::redraw();
+        });
+    }
+
+    let g = graph.clone();
+    let mut e = editor.clone();
+    editor.series.set_callback(move |_| e.load(&g));
+
+    let g = graph.clone();
+    let e = editor.clone();
+    editor.color.set_callback(move |b| {
+        let rgb = color_chooser_with_default("Series color", ColorMode::Byte, b.color().to_rgb());
+        b.set_color(Color::from_rgb(rgb.0, rgb.1, rgb.2));
+        b.redraw();
+        e.apply(&g);
+    });
+
+    let g = graph.clone();
+    let e = editor.clone();
+    editor.style.set_callback(move |_| e.apply(&g));
+    let g = graph.clone();
+    let e = editor.clone();
+    editor.width.set_callback(move |_| e.apply(&g));
+    let g = graph.clone();
+    let e = editor.clone();
+    editor.visible.set_callback(move |_| e.apply(&g));
+
+    // Every call builds a new dialog, so closing it deletes the window and its callbacks.
+    // The deletion is deferred by FLTK, which makes it safe from the callbacks themselves.
+    let win = window.clone();
+    close.set_callback(move |_| fltk::app::delete_widget(win.clone()));
+    window.set_callback(|w| fltk::app::delete_widget(w.clone()));
+
+    window.show();
+    window
+}
@@ -0,0 +1,163 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use fltk::{prelude::*, button::*, dialog::*, enums::*, group::Flex, surface::*, window::*};
+
+use crate::widgets::{graph_2d_widget::Graph2DWidget, interaction::InteractionMode};
+use crate::windows::figure_layout::FigureLayout;
+
+// The order of the radio buttons in the toolbar.
+pub const MODES: [(InteractionMode, &str, &str); 8] = [
+    (
+        InteractionMode::Auto,
+        "Auto",
+        "Data tips on points, zoom box elsewhere",
+    ),
+    (InteractionMode::ZoomBox, "Zoom", "Zoom to a box"),
+    (
+        InteractionMode::ZoomX,
+        "Zoom X",
+        "Zoom along the x-axis only",
+    ),
+    (
+        InteractionMode::ZoomY,
+        "Zoom Y",
+        "Zoom along the y-axis only",
+    ),
+    (InteractionMode::Pan, "Pan", "Pan the view"),
+    (
+        InteractionMode::DataCursor,
+        "Tip",
+        "Place and move data tips",
+    ),
+    (
+        InteractionMode::EditData,
+        "Edit",
+        "Move, insert and delete data points",
+    ),
+    (InteractionMode::Measure, "Measure", "Measurement cursors"),
+];
+
+const BUTTON_WIDTH: i32 = 64;
+
+// Applies the interaction mode to all subplots of the layout.
+pub fn set_mode(layout: &Rc<RefCell<FigureLayout>>, mode: InteractionMode) {
+    layout.borrow_mut().mode = mode;
+
+    for mut graph in subplots(layout) {
+        graph.set_mode(mode);
+    }
+
+    let toolbar = layout.borrow().toolbar.clone();
+    if let Some(toolbar) = toolbar {
+        for (i, (m, _, _)) in MODES.iter().enumerate() {
+            if let Some(mut button) = toolbar
+                .child(i as i32)
+                .and_then(|w| RadioButton::from_dyn_widget(&w))
+            {
+                button.set_value(*m == mode);
+            }
+        }
+    }
+}
+
+// The graphs are collected first, so callbacks fired by the graphs can use the layout again.
+fn subplots(layout: &Rc<RefCell<FigureLayout>>) -> Vec<Graph2DWidget> {
+    layout.borrow().subplots.iter().flatten().cloned().collect()
+}
+
+// Writes the window, without the toolbar, to an SVG file.
+pub fn write_svg(window: &DoubleWindow, top: i32, path: &Path) -> std::io::Result<()> {
+    // The surface panics if it cannot create the file, so check that first.
+    std::fs::File::create(path)?;
+
+    let surface = SvgFileSurface::new(window.width(), window.height() - top, path);
+    SvgFileSurface::push_current(&surface);
+    surface.draw(window, 0, -top);
+    SvgFileSurface::pop_current();
+
+    Ok(())
+}
+
+pub fn create_toolbar(layout: Rc<RefCell<FigureLayout>>, window: DoubleWindow) -> Flex {
+    let mut toolbar = Flex::new(0, 0, window.width(), FigureLayout::TOOLBAR_HEIGHT, None).row();
+    toolbar.set_margin(2);
+    toolbar.set_pad(2);
+
+    let mode = layout.borrow().mode;
+
+    for (m, label, tooltip) in MODES.iter() {
+        let mut button = RadioButton::default().with_label(label);
+        button.set_tooltip(tooltip);
+        button.set_label_size(12);
+        button.set_value(*m == mode);
+        toolbar.fixed(&button, BUTTON_WIDTH);
+
+        let lay = layout.clone();
+        let m = *m;
+        button.set_callback(move |_| set_mode(&lay, m));
+    }
+
+    // Spacer between the modes and the view buttons.
+    let spacer = fltk::frame::Frame::default();
+    toolbar.fixed(&spacer, 10);
+
+    let mut home = Button::default().with_label("Home");
+    home.set_tooltip("Reset the view of all subplots");
+    home.set_label_size(12);
+    toolbar.fixed(&home, BUTTON_WIDTH);
+
+    let lay = layout.clone();
+    home.set_callback(move |_| {
+        for mut graph in subplots(&lay) {
+            graph.reset_view();
+        }
+    });
+
+    let mut back = Button::default().with_label("@<-");
+    back.set_tooltip("Back to the previous view");
+    toolbar.fixed(&back, FigureLayout::TOOLBAR_HEIGHT);
+
+    let lay = layout.clone();
+    back.set_callback(move |_| {
+        for mut graph in subplots(&lay) {
+            graph.view_back();
+        }
+    });
+
+    let mut forward = Button::default().with_label("@->");
+    forward.set_tooltip("Forward to the next view");
+    toolbar.fixed(&forward, FigureLayout::TOOLBAR_HEIGHT);
+
+    let lay = layout.clone();
+    forward.set_callback(move |_| {
+        for mut graph in subplots(&lay) {
+            graph.view_forward();
+        }
+    });
+
+    let mut export = Button::default().with_label("Export");
+    export.set_tooltip("Save the figure as SVG");
+    export.set_label_size(12);
+    toolbar.fixed(&export, BUTTON_WIDTH);
+
+    export.set_callback(move |_| {
+        let mut chooser = FileDialog::new(FileDialogType::BrowseSaveFile);
+        chooser.set_option(FileDialogOptions::SaveAsConfirm);
+        chooser.set_filter("*.svg");
+        chooser.show();
+
+        let path = chooser.filename();
+        if !path.as_os_str().is_empty() {
+            if let Err(e) = write_svg(&window, FigureLayout::TOOLBAR_HEIGHT, &path) {
+                alert_default(&format!("Could not export figure: {}", e));
+            }
+        }
+    });
+
+    toolbar.end();
+    toolbar.set_frame(FrameType::FlatBox);
+
+    toolbar
+}